@@ -1,3 +1,5 @@
+  -- the stats are kept up to date by the streams and the schedule, see collection_stats.
+  -- collections without a stats row yet (e.g. indexed before it existed) show zeros until rebuild_stats runs
   CREATE OR REPLACE VIEW "collection_view" AS
       SELECT 
       "c"."address", 
@@ -9,55 +11,17 @@
       "c"."banner", 
       "c"."description", 
       "c"."socials", 
-      count("l"."id") "listed",
-      coalesce(min("l"."price"),0) "floor_price",
-      coalesce(max("l"."price"),0) "ceiling_price",
-      (
-          SELECT count("t"."id")
-          FROM "public"."transaction" "t"
-          WHERE "t"."collection_address" = "c"."address"
-      ) "sales",
-      (
-          SELECT coalesce(sum("t"."volume"),0)
-          FROM "public"."transaction" "t"
-          WHERE "t"."collection_address" = "c"."address"
-      ) "volume",
-      (
-          SELECT coalesce(sum("t"."volume"),0)
-          FROM "public"."transaction" "t"
-          WHERE "t"."collection_address" = "c"."address"
-          AND "t"."date" > NOW() - INTERVAL '1 hour'
-      ) "volume_of_1h",
-      (
-          SELECT coalesce(sum("t"."volume"),0)
-          FROM "public"."transaction" "t"
-          WHERE "t"."collection_address" = "c"."address"
-          AND "t"."date" > NOW() - INTERVAL '1 day'
-      ) "volume_of_24h",
-      (
-          SELECT coalesce(sum("t"."volume"),0)
-          FROM "public"."transaction" "t"
-          WHERE "t"."collection_address" = "c"."address"
-          AND "t"."date" > NOW() - INTERVAL '7 days'
-      ) "volume_of_7d",
-      (
-          SELECT coalesce(sum("t"."volume"),0)
-          FROM "public"."transaction" "t"
-          WHERE "t"."collection_address" = "c"."address"
-          AND "t"."date" > NOW() - INTERVAL '30 days'
-      ) "volume_of_30d"
+      "c"."slug", 
+      coalesce("s"."listed", 0)::bigint "listed",
+      coalesce("s"."floor_price", 0)::decimal(90, 2) "floor_price",
+      coalesce("s"."ceiling_price", 0)::decimal(90, 2) "ceiling_price",
+      coalesce("s"."sales", 0)::bigint "sales",
+      coalesce("s"."volume", 0)::decimal(90, 2) "volume",
+      coalesce("s"."volume_of_1h", 0)::decimal(90, 2) "volume_of_1h",
+      coalesce("s"."volume_of_24h", 0)::decimal(90, 2) "volume_of_24h",
+      coalesce("s"."volume_of_7d", 0)::decimal(90, 2) "volume_of_7d",
+      coalesce("s"."volume_of_30d", 0)::decimal(90, 2) "volume_of_30d",
+      "c"."royalty_address"
 
       FROM "public"."collection" "c"
-      LEFT JOIN "public"."nft" "n" ON "n"."token_address" = "c"."address"
-      LEFT JOIN "public"."listing_nft" "l" 
-          ON "l"."nft_id" = "n"."id" 
-          AND ("l"."expiration_time" IS NULL OR "l"."expiration_time" > EXTRACT(epoch FROM NOW()))
-      GROUP BY "c"."address";
-
-
-
-
-
-
-
-     
\ No newline at end of file
+      LEFT JOIN "public"."collection_stats" "s" ON "s"."collection_address" = "c"."address";
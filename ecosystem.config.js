@@ -11,10 +11,23 @@ module.exports = {
     {
       name: "cw721-stream",
       script: "./target/release/cw721-stream"
+    },
+    {
+      name: "erc721-stream",
+      script: "./target/release/erc721-stream"
+    },
+    {
+      name: "launchpad-stream",
+      script: "./target/release/launchpad-stream"
     }
     // {
     //   name: "schedule",
     //   script: "./target/release/schedule",
     // },
+    // {
+    //   name: "supervisor",
+    //   script: "./target/release/supervisor",
+    //   args: "server contract-stream erc721-stream"
+    // },
   ]
 };
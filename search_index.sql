@@ -0,0 +1,9 @@
+  -- trigram indexes behind the search endpoint, matched on lowercased values
+  CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+  CREATE INDEX IF NOT EXISTS "collection_name_trgm_idx" ON "public"."collection" USING gin (lower("name") gin_trgm_ops);
+  CREATE INDEX IF NOT EXISTS "collection_symbol_trgm_idx" ON "public"."collection" USING gin (lower("symbol") gin_trgm_ops);
+  CREATE INDEX IF NOT EXISTS "collection_address_trgm_idx" ON "public"."collection" USING gin (lower("address") gin_trgm_ops);
+  CREATE INDEX IF NOT EXISTS "nft_name_trgm_idx" ON "public"."nft" USING gin (lower("name") gin_trgm_ops);
+  CREATE INDEX IF NOT EXISTS "nft_token_id_idx" ON "public"."nft" ("token_id");
+  CREATE INDEX IF NOT EXISTS "nft_trait_value_trgm_idx" ON "public"."nft_trait" USING gin (lower("value") gin_trgm_ops);
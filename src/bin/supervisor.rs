@@ -0,0 +1,4 @@
+#[tokio::main]
+async fn main() {
+    oxide_sei_market::supervisor().await;
+}
@@ -0,0 +1,204 @@
+use crate::error::AppError;
+use deadpool_redis::{
+    redis::{AsyncCommands, ExistenceCheck, RedisError, SetExpiry, SetOptions},
+    Pool,
+};
+use serde_json::Value;
+use std::{future::Future, time::Duration};
+use tokio::time::Instant;
+
+pub static COLLECTIONS_TAG: &str = "collections";
+
+static LOCK_TTL_MS: usize = 10_000;
+static LOCK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+// past this the lock holder is probably gone, so we compute instead of waiting for it
+static LOCK_MAX_WAIT: Duration = Duration::from_secs(5);
+// a tag only indexes keys, it has to outlive the longest ttl of the responses it points to
+static TAG_TTL_SECONDS: i64 = 86_400;
+
+pub fn collection_tag(address_or_slug: &str) -> String {
+    format!("collection:{}", address_or_slug)
+}
+
+// query params are sorted so `?a=1&b=2` and `?b=2&a=1` share the same entry
+pub fn response_key(path: &str, query: Option<&str>) -> String {
+    let mut params: Vec<&str> = query
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .collect();
+
+    params.sort_unstable();
+
+    format!("cache:{}?{}", path, params.join("&"))
+}
+
+// the cache is best effort, whenever redis is unavailable the value is just computed
+pub async fn get_or_compute<F, Fut>(
+    pool: &Pool,
+    key: &str,
+    ttl_seconds: u64,
+    tags: &[String],
+    compute: F,
+) -> Result<Value, AppError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Value, AppError>>,
+{
+    let mut redis = match pool.get().await {
+        Ok(redis) => redis,
+        Err(error) => {
+            eprintln!(
+                "cache is skipped, can not get redis connection \n>>{}",
+                error
+            );
+            return compute().await;
+        }
+    };
+
+    // an invalidation bumps the generations of its tags, so a compute that was already running
+    // stores its stale result under a key that is not read anymore
+    let key = match find_generations(&mut redis, tags).await {
+        Ok(generations) => format!("{}#{}", key, generations.join(":")),
+        Err(error) => {
+            eprintln!(
+                "cache is skipped, can not read generations of {} \n>>{}",
+                key, error
+            );
+            return compute().await;
+        }
+    };
+    let key = key.as_str();
+
+    match find_cached(&mut redis, key).await {
+        Ok(Some(value)) => return Ok(value),
+        Ok(None) => {}
+        Err(error) => {
+            eprintln!("cache is skipped, can not read {} \n>>{}", key, error);
+            return compute().await;
+        }
+    }
+
+    let lock_key = format!("lock:{}", key);
+
+    // single flight, only the lock holder runs the expensive query while the others wait for its result
+    let locked = redis
+        .set_options::<_, _, Option<String>>(
+            &lock_key,
+            "1",
+            SetOptions::default()
+                .conditional_set(ExistenceCheck::NX)
+                .with_expiration(SetExpiry::PX(LOCK_TTL_MS)),
+        )
+        .await
+        .map(|reply| reply.is_some())
+        .unwrap_or(false);
+
+    if !locked {
+        let waited_at = Instant::now();
+
+        while waited_at.elapsed() < LOCK_MAX_WAIT {
+            tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+
+            if let Ok(Some(value)) = find_cached(&mut redis, key).await {
+                return Ok(value);
+            }
+        }
+
+        return compute().await;
+    }
+
+    let result = compute().await;
+
+    if let Ok(value) = &result {
+        if let Err(error) = store(&mut redis, key, value, ttl_seconds, tags).await {
+            eprintln!("can not cache {} \n>>{}", key, error);
+        }
+    }
+
+    redis.del::<_, ()>(&lock_key).await.ok();
+
+    result
+}
+
+pub async fn invalidate(pool: &Pool, tags: &[String]) -> Result<(), AppError> {
+    let mut redis = pool.get().await?;
+
+    for tag in tags {
+        let generation_key = generation_key(tag);
+
+        redis.incr::<_, _, ()>(&generation_key, 1).await?;
+        redis
+            .expire::<_, ()>(&generation_key, TAG_TTL_SECONDS)
+            .await?;
+
+        // the old entries are unreachable already, they are only dropped to free the memory
+        let tag_key = format!("cache_tag:{}", tag);
+        let keys: Vec<String> = redis.smembers(&tag_key).await?;
+
+        if !keys.is_empty() {
+            redis.del::<_, ()>(keys).await?;
+        }
+
+        redis.del::<_, ()>(&tag_key).await?;
+    }
+
+    Ok(())
+}
+
+async fn find_generations(
+    redis: &mut deadpool_redis::Connection,
+    tags: &[String],
+) -> Result<Vec<String>, RedisError> {
+    if tags.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let keys: Vec<String> = tags.iter().map(|tag| generation_key(tag)).collect();
+
+    // mget sends a plain GET for a single key, its reply would not parse into a vec
+    let generations: Vec<Option<u64>> = deadpool_redis::redis::cmd("MGET")
+        .arg(keys)
+        .query_async(redis)
+        .await?;
+
+    Ok(generations
+        .into_iter()
+        .map(|generation| generation.unwrap_or_default().to_string())
+        .collect())
+}
+
+fn generation_key(tag: &str) -> String {
+    format!("cache_generation:{}", tag)
+}
+
+async fn find_cached(
+    redis: &mut deadpool_redis::Connection,
+    key: &str,
+) -> Result<Option<Value>, RedisError> {
+    let cached: Option<String> = redis.get(key).await?;
+
+    // an entry that does not parse anymore is treated as a miss and overwritten
+    Ok(cached.and_then(|cached| serde_json::from_str(&cached).ok()))
+}
+
+async fn store(
+    redis: &mut deadpool_redis::Connection,
+    key: &str,
+    value: &Value,
+    ttl_seconds: u64,
+    tags: &[String],
+) -> Result<(), RedisError> {
+    redis
+        .set_ex::<_, _, ()>(key, value.to_string(), ttl_seconds)
+        .await?;
+
+    for tag in tags {
+        let tag_key = format!("cache_tag:{}", tag);
+
+        redis.sadd::<_, _, ()>(&tag_key, key).await?;
+        redis.expire::<_, ()>(&tag_key, TAG_TTL_SECONDS).await?;
+    }
+
+    Ok(())
+}
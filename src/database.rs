@@ -1,4 +1,24 @@
 mod entity;
 pub mod model;
 pub mod repository;
+#[cfg(all(test, feature = "fake"))]
+pub mod test;
 pub use entity::sea_orm_active_enums::*;
+// entity models that are served as they are
+pub use entity::{
+    collection_view::Model as CollectionView, listing_nft::Model as ListingNft, nft::Model as Nft,
+    nft_activity::Model as NftActivity, nft_bidding::Model as NftBidding,
+    nft_offer::Model as NftOffer, nft_rarity::Model as NftRarity,
+    user_loyalty_point::Model as UserLoyaltyPoint,
+};
+
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+
+pub async fn connect() -> DatabaseConnection {
+    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.sqlx_logging(false);
+
+    Database::connect(opt).await.unwrap()
+}
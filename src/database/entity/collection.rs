@@ -15,19 +15,34 @@ pub struct Model {
     pub description: Option<String>,
     #[sea_orm(column_type = "Decimal(Some((90, 2)))", nullable)]
     pub royalty: Option<Decimal>,
+    pub royalty_address: Option<String>,
     pub supply: i32,
     #[sea_orm(column_type = "JsonBinary", nullable)]
     pub socials: Option<Json>,
+    #[sea_orm(unique)]
+    pub evm_address: Option<String>,
+    #[sea_orm(unique)]
+    pub slug: Option<String>,
+    pub rarity_outdated: bool,
+    pub metadata_refreshed_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_one = "super::collection_stats::Entity")]
+    CollectionStats,
     #[sea_orm(has_many = "super::nft::Entity")]
     Nft,
     #[sea_orm(has_many = "super::transaction::Entity")]
     Transaction,
 }
 
+impl Related<super::collection_stats::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CollectionStats.def()
+    }
+}
+
 impl Related<super::nft::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Nft.def()
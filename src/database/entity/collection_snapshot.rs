@@ -14,6 +14,9 @@ pub struct Model {
     pub floor: Decimal,
     #[sea_orm(column_type = "Decimal(Some((90, 2)))")]
     pub volume_of_24h: Decimal,
+    pub listed: i32,
+    pub sales_of_24h: i32,
+    pub holders: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
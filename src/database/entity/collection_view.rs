@@ -15,11 +15,15 @@ pub struct Model {
     #[sea_orm(column_type = "Decimal(Some((90, 2)))", nullable)]
     pub royalty: Option<Decimal>,
 
+    pub royalty_address: Option<String>,
+
     pub supply: i32,
 
     #[sea_orm(column_type = "JsonBinary", nullable)]
     pub socials: Option<Json>,
 
+    pub slug: Option<String>,
+
     pub listed: i64,
 
     #[sea_orm(column_type = "Decimal(Some((90, 2)))")]
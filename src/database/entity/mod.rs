@@ -1,18 +1,25 @@
 pub mod collection;
 pub mod collection_offer;
 pub mod collection_snapshot;
+pub mod collection_stats;
 pub mod collection_view;
 pub mod config;
 pub mod failure_stream_tx;
+pub mod launchpad;
+pub mod launchpad_mint;
+pub mod launchpad_phase;
 pub mod listing_nft;
+pub mod metadata_refresh;
 pub mod missing_stream_block;
 pub mod nft;
 pub mod nft_activity;
 pub mod nft_bidding;
 pub mod nft_offer;
+pub mod nft_rarity;
 pub mod nft_trait;
 pub mod sea_orm_active_enums;
 pub mod stream_tx;
 pub mod transaction;
 pub mod user;
 pub mod user_loyalty_point;
+pub mod wallet_link;
@@ -27,12 +27,16 @@ pub enum Relation {
         on_delete = "Restrict"
     )]
     Collection,
+    #[sea_orm(has_many = "super::launchpad_mint::Entity")]
+    LaunchpadMint,
     #[sea_orm(has_many = "super::listing_nft::Entity")]
     ListingNft,
     #[sea_orm(has_many = "super::nft_activity::Entity")]
     NftActivity,
     #[sea_orm(has_many = "super::nft_offer::Entity")]
     NftOffer,
+    #[sea_orm(has_many = "super::nft_rarity::Entity")]
+    NftRarity,
     #[sea_orm(has_many = "super::nft_trait::Entity")]
     NftTrait,
 }
@@ -43,6 +47,12 @@ impl Related<super::collection::Entity> for Entity {
     }
 }
 
+impl Related<super::launchpad_mint::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LaunchpadMint.def()
+    }
+}
+
 impl Related<super::listing_nft::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ListingNft.def()
@@ -61,6 +71,12 @@ impl Related<super::nft_offer::Entity> for Entity {
     }
 }
 
+impl Related<super::nft_rarity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::NftRarity.def()
+    }
+}
+
 impl Related<super::nft_trait::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::NftTrait.def()
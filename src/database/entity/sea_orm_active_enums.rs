@@ -38,6 +38,16 @@ pub enum NftActivityKind {
     Sale,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "rarity_method")]
+pub enum RarityMethod {
+    #[sea_orm(string_value = "information_content")]
+    InformationContent,
+    #[sea_orm(string_value = "statistical")]
+    Statistical,
+    #[sea_orm(string_value = "trait_rarity_sum")]
+    TraitRaritySum,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "sale_type")]
 pub enum SaleType {
     #[sea_orm(string_value = "auction")]
@@ -50,6 +60,8 @@ pub enum SaleType {
 pub enum StreamContext {
     #[sea_orm(string_value = "cwr721")]
     Cwr721,
+    #[sea_orm(string_value = "erc721")]
+    Erc721,
     #[sea_orm(string_value = "launchpad")]
     Launchpad,
     #[sea_orm(string_value = "mrkt")]
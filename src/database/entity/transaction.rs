@@ -17,6 +17,9 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub market: Marketplace,
+    #[sea_orm(column_type = "Decimal(Some((90, 2)))")]
+    pub royalty: Decimal,
+    pub royalty_address: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,23 @@
+use crate::database::{Marketplace, NftActivityKind};
+use sea_orm::{
+    prelude::{DateTimeWithTimeZone, Decimal},
+    FromQueryResult,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(FromQueryResult, Serialize, Deserialize, Clone)]
+pub struct Activity {
+    pub id: i32,
+    pub tx_hash: String,
+    pub event_kind: NftActivityKind,
+    pub market: Marketplace,
+    pub price: Decimal,
+    pub denom: String,
+    pub seller_address: Option<String>,
+    pub buyer_address: Option<String>,
+    pub date: DateTimeWithTimeZone,
+    pub token_address: String,
+    pub token_id: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+}
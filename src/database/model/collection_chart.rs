@@ -0,0 +1,19 @@
+use sea_orm::{prelude::Decimal, FromQueryResult};
+
+// bucket is the index of the interval since the unix epoch, `bucket * interval` is its start
+#[derive(FromQueryResult, Clone)]
+pub struct PriceCandle {
+    pub bucket: i64,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal,
+    pub sales: i64,
+}
+
+#[derive(FromQueryResult, Clone)]
+pub struct FloorPoint {
+    pub bucket: i64,
+    pub floor: Decimal,
+}
@@ -0,0 +1,10 @@
+use sea_orm::{prelude::Decimal, FromQueryResult};
+
+// bucket is the index of the interval since the unix epoch, like the chart ones
+#[derive(FromQueryResult, Clone)]
+pub struct RoyaltyPoint {
+    pub bucket: i64,
+    pub royalty: Decimal,
+    pub volume: Decimal,
+    pub sales: i64,
+}
@@ -1,6 +1,7 @@
 use sea_orm::{prelude::Decimal, FromQueryResult};
+use serde::Serialize;
 
-#[derive(FromQueryResult, Default, Clone)]
+#[derive(FromQueryResult, Serialize, Default, Clone)]
 pub struct LeaderboardParticipant {
     pub wallet_address: String,
     pub rank: i64,
@@ -0,0 +1,23 @@
+use crate::database::{Marketplace, SaleType};
+use sea_orm::{
+    prelude::{DateTimeWithTimeZone, Decimal},
+    FromQueryResult,
+};
+use serde::Serialize;
+
+#[derive(FromQueryResult, Serialize, Clone)]
+pub struct ListedNft {
+    pub nft_id: i32,
+    pub token_address: String,
+    pub token_id: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub price: Decimal,
+    pub denom: String,
+    pub market: Marketplace,
+    pub sale_type: SaleType,
+    pub seller_address: String,
+    pub listed_date: DateTimeWithTimeZone,
+    pub expiration_time: Option<i32>,
+    pub rarity_rank: Option<i32>,
+}
@@ -0,0 +1,16 @@
+use crate::database::Marketplace;
+use sea_orm::{prelude::Decimal, FromQueryResult};
+
+#[derive(FromQueryResult, Clone)]
+pub struct MarketplaceFloor {
+    pub market: Marketplace,
+    pub floor_price: Decimal,
+    pub listed: i64,
+}
+
+#[derive(FromQueryResult, Clone)]
+pub struct MarketplaceVolume {
+    pub market: Marketplace,
+    pub volume: Decimal,
+    pub sales: i64,
+}
@@ -1,5 +1,35 @@
+mod activity;
+mod collection_chart;
+mod collection_royalty;
 mod count;
 mod leaderboard_participant;
+mod listed_nft;
+mod marketplace_stats;
+mod nft_offer_detail;
+mod nft_trait_value;
+mod offer_price_level;
+mod owned_collection;
+mod point_total;
+mod sale_price;
+mod search_result;
+mod trait_facet;
+mod trait_rarity;
+mod wallet_mint;
 
+pub use activity::*;
+pub use collection_chart::*;
+pub use collection_royalty::*;
 pub use count::*;
 pub use leaderboard_participant::*;
+pub use listed_nft::*;
+pub use marketplace_stats::*;
+pub use nft_offer_detail::*;
+pub use nft_trait_value::*;
+pub use offer_price_level::*;
+pub use owned_collection::*;
+pub use point_total::*;
+pub use sale_price::*;
+pub use search_result::*;
+pub use trait_facet::*;
+pub use trait_rarity::*;
+pub use wallet_mint::*;
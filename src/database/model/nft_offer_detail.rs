@@ -0,0 +1,21 @@
+use sea_orm::{
+    prelude::{DateTimeWithTimeZone, Decimal},
+    FromQueryResult,
+};
+use serde::Serialize;
+
+#[derive(FromQueryResult, Serialize, Clone)]
+pub struct NftOfferDetail {
+    pub id: i32,
+    pub price: Decimal,
+    pub denom: String,
+    pub buyer_address: String,
+    pub created_date: DateTimeWithTimeZone,
+    pub start_date: DateTimeWithTimeZone,
+    pub end_date: DateTimeWithTimeZone,
+    pub token_address: String,
+    pub token_id: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub owner_address: Option<String>,
+}
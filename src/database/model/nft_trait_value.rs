@@ -0,0 +1,8 @@
+use sea_orm::FromQueryResult;
+
+#[derive(FromQueryResult, Clone)]
+pub struct NftTraitValue {
+    pub nft_id: i32,
+    pub attribute: String,
+    pub value: String,
+}
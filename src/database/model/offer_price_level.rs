@@ -0,0 +1,10 @@
+use sea_orm::{prelude::Decimal, FromQueryResult};
+use serde::Serialize;
+
+#[derive(FromQueryResult, Serialize, Clone)]
+pub struct OfferPriceLevel {
+    pub price: Decimal,
+    pub denom: String,
+    pub quantity: i64,
+    pub bidders: i64,
+}
@@ -0,0 +1,12 @@
+use sea_orm::{prelude::Decimal, FromQueryResult};
+use serde::Serialize;
+
+#[derive(FromQueryResult, Serialize, Clone)]
+pub struct OwnedCollection {
+    pub address: String,
+    pub name: String,
+    pub image: Option<String>,
+    pub floor_price: Decimal,
+    pub owned: i64,
+    pub estimated_value: Decimal,
+}
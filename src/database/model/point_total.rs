@@ -0,0 +1,9 @@
+use crate::database::LoyaltyPointKind;
+use sea_orm::FromQueryResult;
+use serde::Serialize;
+
+#[derive(FromQueryResult, Serialize, Clone)]
+pub struct PointTotal {
+    pub kind: LoyaltyPointKind,
+    pub point: i64,
+}
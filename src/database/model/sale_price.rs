@@ -0,0 +1,14 @@
+use crate::database::Marketplace;
+use sea_orm::{
+    prelude::{DateTimeWithTimeZone, Decimal},
+    FromQueryResult,
+};
+use serde::Serialize;
+
+#[derive(FromQueryResult, Serialize, Clone)]
+pub struct SalePrice {
+    pub price: Decimal,
+    pub denom: String,
+    pub market: Marketplace,
+    pub date: DateTimeWithTimeZone,
+}
@@ -0,0 +1,14 @@
+use sea_orm::{prelude::Decimal, FromQueryResult};
+use serde::Serialize;
+
+// address is the collection address for both kinds, token_id is only set for nfts
+#[derive(FromQueryResult, Serialize, Clone)]
+pub struct SearchResult {
+    pub kind: String,
+    pub address: String,
+    pub token_id: Option<String>,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub score: f32,
+    pub volume: Decimal,
+}
@@ -0,0 +1,16 @@
+use sea_orm::{prelude::Decimal, FromQueryResult};
+
+#[derive(FromQueryResult, Clone)]
+pub struct TraitCount {
+    pub attribute: String,
+    pub value: String,
+    pub count: i64,
+}
+
+#[derive(FromQueryResult, Clone)]
+pub struct TraitListing {
+    pub attribute: String,
+    pub value: String,
+    pub listed: i64,
+    pub floor_price: Option<Decimal>,
+}
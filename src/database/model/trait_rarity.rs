@@ -0,0 +1,10 @@
+use sea_orm::FromQueryResult;
+use serde::Serialize;
+
+#[derive(FromQueryResult, Serialize, Clone)]
+pub struct TraitRarity {
+    pub attribute: String,
+    pub value: String,
+    pub display_type: Option<String>,
+    pub count: i64,
+}
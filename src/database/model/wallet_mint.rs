@@ -0,0 +1,7 @@
+use sea_orm::FromQueryResult;
+
+#[derive(FromQueryResult, Default, Clone)]
+pub struct WalletMint {
+    pub phase: String,
+    pub minted: i64,
+}
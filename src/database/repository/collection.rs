@@ -1,16 +1,36 @@
 use crate::{
     database::{
-        entity::{collection, collection_view},
-        model::Count,
+        entity::{collection, collection_view, listing_nft, nft, transaction},
+        model::{Count, MarketplaceFloor, MarketplaceVolume},
+        repository::collection_stats as CollectionStatsRepository,
     },
     server::{api::collection::SortBy, deserialization::SortDirection},
     service::CollectionMetadata,
 };
+use chrono::Utc;
 use sea_orm::{
-    prelude::Decimal, sea_query::OnConflict, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
-    QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set,
+    prelude::{DateTimeUtc, Decimal},
+    sea_query::{Expr, Func, OnConflict, SimpleExpr},
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, Statement,
 };
 
+static UNREVEALED_QUERY: &str = r#"
+SELECT "c"."address"
+FROM "collection" "c"
+INNER JOIN "nft" "n" ON "n"."token_address" = "c"."address"
+WHERE "c"."metadata_refreshed_at" IS NULL OR "c"."metadata_refreshed_at" < $1
+GROUP BY "c"."address"
+HAVING (count(*) > 1 AND count(DISTINCT "n"."image") <= 1)
+OR NOT EXISTS (
+    SELECT 1 FROM "nft_trait" "t"
+    INNER JOIN "nft" "m" ON "m"."id" = "t"."nft_id"
+    WHERE "m"."token_address" = "c"."address"
+)
+ORDER BY "c"."metadata_refreshed_at" ASC NULLS FIRST
+LIMIT $2
+"#;
+
 pub async fn find_by_address(
     db: &DatabaseConnection,
     address: &str,
@@ -18,7 +38,36 @@ pub async fn find_by_address(
     collection::Entity::find_by_id(address).one(db).await
 }
 
+pub async fn find_by_evm_address(
+    db: &DatabaseConnection,
+    evm_address: &str,
+) -> Result<Option<collection::Model>, DbErr> {
+    collection::Entity::find()
+        .filter(collection::Column::EvmAddress.eq(evm_address.to_lowercase()))
+        .one(db)
+        .await
+}
+
+pub async fn set_evm_address(
+    db: &DatabaseConnection,
+    address: &str,
+    evm_address: &str,
+) -> Result<(), DbErr> {
+    collection::Entity::update_many()
+        .col_expr(
+            collection::Column::EvmAddress,
+            Expr::value(evm_address.to_lowercase()),
+        )
+        .filter(collection::Column::Address.eq(address))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
 pub async fn create(db: &DatabaseConnection, params: CreateCollectionParams) -> Result<(), DbErr> {
+    let address = params.address.to_owned();
+
     let collection = collection::ActiveModel {
         address: Set(params.address),
         name: Set(params.name),
@@ -26,28 +75,121 @@ pub async fn create(db: &DatabaseConnection, params: CreateCollectionParams) ->
         supply: Set(params.supply),
         description: Set(params.metadata.description),
         royalty: Set(params.royalty),
+        royalty_address: Set(params.royalty_address),
         banner: Set(params.metadata.banner),
         image: Set(params.metadata.pfp),
         socials: Set(params.metadata.socials),
-        ..Default::default()
+        evm_address: Set(params.evm_address.map(|address| address.to_lowercase())),
+        slug: Set(params.metadata.slug),
+        rarity_outdated: Set(true),
+        metadata_refreshed_at: Set(None),
     };
 
+    // a collection first seen by the cw721 stream learns its evm address when the erc721 stream meets it
     collection::Entity::insert(collection)
         .on_conflict(
             OnConflict::column(collection::Column::Address)
-                .do_nothing()
+                .value(
+                    collection::Column::EvmAddress,
+                    Expr::cust(r#"coalesce("excluded"."evm_address", "collection"."evm_address")"#),
+                )
                 .to_owned(),
         )
         .exec(db)
+        .await?;
+
+    // collection_view only lists collections that have stats
+    CollectionStatsRepository::create_if_not_exist(db, &address).await
+}
+
+// slug is left alone, it is part of the urls and cache tags of the collection
+pub async fn update_metadata(
+    db: &DatabaseConnection,
+    params: UpdateCollectionMetadataParams,
+) -> Result<(), DbErr> {
+    let collection = collection::ActiveModel {
+        name: Set(params.name),
+        symbol: Set(params.symbol),
+        supply: Set(params.supply),
+        royalty: Set(params.royalty),
+        royalty_address: Set(params.royalty_address),
+        image: Set(params.metadata.pfp),
+        banner: Set(params.metadata.banner),
+        description: Set(params.metadata.description),
+        socials: Set(params.metadata.socials),
+        metadata_refreshed_at: Set(Some(Utc::now().into())),
+        ..Default::default()
+    };
+
+    collection::Entity::update_many()
+        .set(collection)
+        .filter(collection::Column::Address.eq(params.address))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+// a placeholder image on every token or no trait at all is what an unrevealed drop looks like,
+// 1/1 collections without traits match too but they are only rechecked once per interval
+pub async fn find_unrevealed(
+    db: &DatabaseConnection,
+    refreshed_before: DateTimeUtc,
+    limit: u64,
+) -> Result<Vec<String>, DbErr> {
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            UNREVEALED_QUERY,
+            [refreshed_before.into(), (limit as i64).into()],
+        ))
+        .await?;
+
+    rows.iter()
+        .map(|row| row.try_get::<String>("", "address"))
+        .collect()
+}
+
+// a failed recheck still counts, a broken token uri would otherwise be retried every run
+pub async fn set_metadata_refreshed(db: &DatabaseConnection, address: &str) -> Result<(), DbErr> {
+    collection::Entity::update_many()
+        .col_expr(
+            collection::Column::MetadataRefreshedAt,
+            Expr::value(Utc::now()),
+        )
+        .filter(collection::Column::Address.eq(address))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+// collections whose nfts or traits changed since their rarity was last computed
+pub async fn find_rarity_outdated(db: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+    collection::Entity::find()
+        .select_only()
+        .column(collection::Column::Address)
+        .filter(collection::Column::RarityOutdated.eq(true))
+        .into_tuple::<String>()
+        .all(db)
         .await
-        .map(|_| ())
-        .or_else(|error| {
-            if let DbErr::RecordNotInserted = error {
-                Ok(())
-            } else {
-                Err(error)
-            }
-        })
+}
+
+pub async fn set_rarity_outdated(
+    db: &DatabaseConnection,
+    address: &str,
+    rarity_outdated: bool,
+) -> Result<(), DbErr> {
+    collection::Entity::update_many()
+        .col_expr(
+            collection::Column::RarityOutdated,
+            Expr::value(rarity_outdated),
+        )
+        .filter(collection::Column::Address.eq(address))
+        .exec(db)
+        .await?;
+
+    Ok(())
 }
 
 pub async fn find_collections_with_stats(
@@ -70,7 +212,7 @@ pub async fn find_collections_with_stats(
 
     let collections = collection_view::Entity::find()
         .apply_if(search.as_ref(), |query, search| {
-            query.filter(collection_view::Column::Name.contains(search))
+            query.filter(name_matches(collection_view::Column::Name, search))
         })
         .order_by(sort_by, sort_direction.into_order())
         .limit(limit as u64)
@@ -82,7 +224,7 @@ pub async fn find_collections_with_stats(
         .select_only()
         .column_as(collection::Column::Address.count(), "count")
         .apply_if(search, |query, search| {
-            query.filter(collection::Column::Name.contains(search))
+            query.filter(name_matches(collection::Column::Name, &search))
         })
         .into_model::<Count>()
         .one(db)
@@ -92,6 +234,94 @@ pub async fn find_collections_with_stats(
     Ok((collections, total.count))
 }
 
+// the slug comes from the curated metadata, so pages can be linked by name instead of by contract
+pub async fn find_collection_with_stats(
+    db: &DatabaseConnection,
+    address_or_slug: &str,
+) -> Result<Option<collection_view::Model>, DbErr> {
+    collection_view::Entity::find()
+        .filter(
+            Condition::any()
+                .add(collection_view::Column::Address.eq(address_or_slug))
+                .add(collection_view::Column::Slug.eq(address_or_slug)),
+        )
+        .one(db)
+        .await
+}
+
+pub async fn count_holders(db: &DatabaseConnection, address: &str) -> Result<i64, DbErr> {
+    let total = nft::Entity::find()
+        .select_only()
+        .column_as(
+            Expr::col(nft::Column::OwnerAddress).count_distinct(),
+            "count",
+        )
+        .filter(nft::Column::TokenAddress.eq(address))
+        .filter(nft::Column::OwnerAddress.is_not_null())
+        .into_model::<Count>()
+        .one(db)
+        .await?
+        .unwrap_or_default();
+
+    Ok(total.count)
+}
+
+pub async fn find_marketplace_floors(
+    db: &DatabaseConnection,
+    address: &str,
+) -> Result<Vec<MarketplaceFloor>, DbErr> {
+    listing_nft::Entity::find()
+        .select_only()
+        .column(listing_nft::Column::Market)
+        .column_as(listing_nft::Column::Price.min(), "floor_price")
+        .column_as(listing_nft::Column::Id.count(), "listed")
+        .filter(listing_nft::Column::CollectionAddress.eq(address))
+        .filter(
+            Condition::any()
+                .add(listing_nft::Column::ExpirationTime.is_null())
+                .add(
+                    Expr::col(listing_nft::Column::ExpirationTime)
+                        .gt(Expr::cust("EXTRACT(epoch FROM NOW())")),
+                ),
+        )
+        .group_by(listing_nft::Column::Market)
+        .into_model::<MarketplaceFloor>()
+        .all(db)
+        .await
+}
+
+pub async fn find_marketplace_volumes(
+    db: &DatabaseConnection,
+    address: &str,
+) -> Result<Vec<MarketplaceVolume>, DbErr> {
+    transaction::Entity::find()
+        .select_only()
+        .column(transaction::Column::Market)
+        .column_as(transaction::Column::Volume.sum(), "volume")
+        .column_as(transaction::Column::Id.count(), "sales")
+        .filter(transaction::Column::CollectionAddress.eq(address))
+        .group_by(transaction::Column::Market)
+        .into_model::<MarketplaceVolume>()
+        .all(db)
+        .await
+}
+
+// case insensitive, served by the trigram index on lower(name), see search_index.sql
+fn name_matches(column: impl ColumnTrait, search: &str) -> SimpleExpr {
+    Expr::expr(Func::lower(Expr::col((column.entity_name(), column))))
+        .like(format!("%{}%", search.to_lowercase()))
+}
+
+pub struct UpdateCollectionMetadataParams {
+    pub address: String,
+    pub name: String,
+    pub symbol: String,
+    pub supply: i32,
+    pub royalty: Option<Decimal>,
+    pub royalty_address: Option<String>,
+    pub metadata: CollectionMetadata,
+}
+
 pub struct CreateCollectionParams {
     pub address: String,
     pub name: String,
@@ -99,4 +329,6 @@ pub struct CreateCollectionParams {
     pub supply: i32,
     pub metadata: CollectionMetadata,
     pub royalty: Option<Decimal>,
+    pub royalty_address: Option<String>,
+    pub evm_address: Option<String>,
 }
@@ -0,0 +1,72 @@
+use crate::database::{entity::collection_offer, model::OfferPriceLevel};
+use sea_orm::{
+    sea_query::{Expr, Func, SimpleExpr},
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+// bids are aggregated per price so the depth reads like an order book, best bid first
+pub async fn find_price_levels(
+    db: &DatabaseConnection,
+    collection_address: &str,
+) -> Result<Vec<OfferPriceLevel>, DbErr> {
+    collection_offer::Entity::find()
+        .select_only()
+        .column(collection_offer::Column::Price)
+        .column(collection_offer::Column::Denom)
+        .column_as(
+            SimpleExpr::from(Func::sum(
+                Expr::col(collection_offer::Column::Quantity)
+                    .sub(Expr::col(collection_offer::Column::CurrentQuantity)),
+            )),
+            "quantity",
+        )
+        .column_as(
+            Expr::col(collection_offer::Column::BuyerAddress).count_distinct(),
+            "bidders",
+        )
+        .filter(collection_offer::Column::CollectionAddress.eq(collection_address))
+        .filter(active_offer_condition())
+        .group_by(collection_offer::Column::Price)
+        .group_by(collection_offer::Column::Denom)
+        .order_by_desc(collection_offer::Column::Price)
+        .into_model::<OfferPriceLevel>()
+        .all(db)
+        .await
+}
+
+pub async fn find_active_by_buyer(
+    db: &DatabaseConnection,
+    collection_address: &str,
+    buyer_address: &str,
+) -> Result<Vec<collection_offer::Model>, DbErr> {
+    collection_offer::Entity::find()
+        .filter(collection_offer::Column::CollectionAddress.eq(collection_address))
+        .filter(collection_offer::Column::BuyerAddress.eq(buyer_address))
+        .filter(active_offer_condition())
+        .order_by_desc(collection_offer::Column::Price)
+        .all(db)
+        .await
+}
+
+pub async fn find_all_active_by_buyers(
+    db: &DatabaseConnection,
+    buyers: &[String],
+) -> Result<Vec<collection_offer::Model>, DbErr> {
+    collection_offer::Entity::find()
+        .filter(collection_offer::Column::BuyerAddress.is_in(buyers))
+        .filter(active_offer_condition())
+        .order_by_desc(collection_offer::Column::CreatedDate)
+        .all(db)
+        .await
+}
+
+// current_quantity counts the nfts already sold into the offer
+fn active_offer_condition() -> Condition {
+    Condition::all()
+        .add(Expr::col(collection_offer::Column::EndDate).gt(Expr::current_timestamp()))
+        .add(
+            Expr::col(collection_offer::Column::CurrentQuantity)
+                .lt(Expr::col(collection_offer::Column::Quantity)),
+        )
+}
@@ -0,0 +1,159 @@
+use crate::database::{
+    entity::{collection_snapshot, collection_view, nft, transaction},
+    model::FloorPoint,
+};
+use chrono::Duration;
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sea_query::{Expr, Query, SimpleExpr},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Statement,
+};
+
+// one row per collection, every row of a run shares the same date so they line up on charts
+pub async fn create_snapshots(db: &DatabaseConnection, date: DateTimeUtc) -> Result<u64, DbErr> {
+    let sales_of_24h = Query::select()
+        .expr(Expr::col((transaction::Entity, transaction::Column::Id)).count())
+        .from(transaction::Entity)
+        .and_where(
+            Expr::col((transaction::Entity, transaction::Column::CollectionAddress))
+                .equals((collection_view::Entity, collection_view::Column::Address)),
+        )
+        .and_where(
+            Expr::col((transaction::Entity, transaction::Column::Date))
+                .gt(date - Duration::days(1)),
+        )
+        .and_where(Expr::col((transaction::Entity, transaction::Column::Date)).lte(date))
+        .to_owned();
+
+    let holders = Query::select()
+        .expr(Expr::col((nft::Entity, nft::Column::OwnerAddress)).count_distinct())
+        .from(nft::Entity)
+        .and_where(
+            Expr::col((nft::Entity, nft::Column::TokenAddress))
+                .equals((collection_view::Entity, collection_view::Column::Address)),
+        )
+        .and_where(Expr::col((nft::Entity, nft::Column::OwnerAddress)).is_not_null())
+        .to_owned();
+
+    let snapshots = Query::select()
+        .column(collection_view::Column::Address)
+        .expr(Expr::val(date))
+        .column(collection_view::Column::FloorPrice)
+        .column(collection_view::Column::VolumeOf24h)
+        .column(collection_view::Column::Listed)
+        .expr(SimpleExpr::SubQuery(
+            None,
+            Box::new(sales_of_24h.into_sub_query_statement()),
+        ))
+        .expr(SimpleExpr::SubQuery(
+            None,
+            Box::new(holders.into_sub_query_statement()),
+        ))
+        .from(collection_view::Entity)
+        .to_owned();
+
+    let insert = Query::insert()
+        .into_table(collection_snapshot::Entity)
+        .columns([
+            collection_snapshot::Column::CollectionAddress,
+            collection_snapshot::Column::Date,
+            collection_snapshot::Column::Floor,
+            collection_snapshot::Column::VolumeOf24h,
+            collection_snapshot::Column::Listed,
+            collection_snapshot::Column::SalesOf24h,
+            collection_snapshot::Column::Holders,
+        ])
+        .select_from(snapshots)
+        .map_err(|e| DbErr::Custom(e.to_string()))?
+        .to_owned();
+
+    let result = db.execute(db.get_database_backend().build(&insert)).await?;
+
+    Ok(result.rows_affected())
+}
+
+// keeps only the latest snapshot of every collection per hour or day for snapshots older than the date
+pub async fn downsample(
+    db: &DatabaseConnection,
+    resolution: SnapshotResolution,
+    older_than: DateTimeUtc,
+) -> Result<u64, DbErr> {
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"DELETE FROM "collection_snapshot" "s"
+            WHERE "s"."date" < $1
+            AND EXISTS (
+                SELECT 1 FROM "collection_snapshot" "o"
+                WHERE "o"."collection_address" = "s"."collection_address"
+                AND date_trunc($2, "o"."date") = date_trunc($2, "s"."date")
+                AND ("o"."date", "o"."id") > ("s"."date", "s"."id")
+            )"#,
+            [older_than.into(), resolution.unit().into()],
+        ))
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+// the floor of a bucket is the one of its latest snapshot
+pub async fn find_floors(
+    db: &DatabaseConnection,
+    address: &str,
+    interval_seconds: i64,
+    from: DateTimeUtc,
+    to: DateTimeUtc,
+) -> Result<Vec<FloorPoint>, DbErr> {
+    collection_snapshot::Entity::find()
+        .select_only()
+        .column_as(
+            Expr::cust(format!(
+                r#"floor(extract(epoch FROM "date") / {})::bigint"#,
+                interval_seconds
+            )),
+            "bucket",
+        )
+        .column_as(
+            Expr::cust(r#"(array_agg("floor" ORDER BY "date" DESC, "id" DESC))[1]"#),
+            "floor",
+        )
+        .filter(collection_snapshot::Column::CollectionAddress.eq(address))
+        .filter(collection_snapshot::Column::Date.gte(from))
+        .filter(collection_snapshot::Column::Date.lt(to))
+        .group_by(Expr::cust("bucket"))
+        .order_by_asc(Expr::cust("bucket"))
+        .into_model::<FloorPoint>()
+        .all(db)
+        .await
+}
+
+pub async fn find_last_floor_before(
+    db: &DatabaseConnection,
+    address: &str,
+    date: DateTimeUtc,
+) -> Result<Option<Decimal>, DbErr> {
+    let snapshot = collection_snapshot::Entity::find()
+        .filter(collection_snapshot::Column::CollectionAddress.eq(address))
+        .filter(collection_snapshot::Column::Date.lt(date))
+        .order_by_desc(collection_snapshot::Column::Date)
+        .order_by_desc(collection_snapshot::Column::Id)
+        .one(db)
+        .await?;
+
+    Ok(snapshot.map(|snapshot| snapshot.floor))
+}
+
+pub enum SnapshotResolution {
+    Hour,
+    Day,
+}
+
+impl SnapshotResolution {
+    fn unit(&self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+}
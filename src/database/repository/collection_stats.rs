@@ -0,0 +1,336 @@
+use crate::database::entity::{collection, collection_stats, listing_nft, transaction};
+use chrono::{Duration, Utc};
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sea_query::{Alias, Expr, Func, OnConflict, Query, SimpleExpr},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, Set,
+};
+
+// a sale only adds to the windows it still falls in, the schedule moves the windows forward
+pub async fn record_sale(
+    tx: &DatabaseTransaction,
+    collection_address: &str,
+    price: Decimal,
+    date: DateTimeUtc,
+) -> Result<(), DbErr> {
+    let age = Utc::now() - date;
+    let in_window = |window: Duration| if age < window { price } else { Decimal::ZERO };
+
+    let stats = collection_stats::ActiveModel {
+        collection_address: Set(collection_address.to_owned()),
+        sales: Set(1),
+        volume: Set(price),
+        volume_of_1h: Set(in_window(Duration::hours(1))),
+        volume_of_24h: Set(in_window(Duration::days(1))),
+        volume_of_7d: Set(in_window(Duration::days(7))),
+        volume_of_30d: Set(in_window(Duration::days(30))),
+        ..Default::default()
+    };
+
+    let excluded = Alias::new("excluded");
+    let increment = |column: collection_stats::Column| {
+        Expr::col((collection_stats::Entity, column)).add(Expr::col((excluded.clone(), column)))
+    };
+
+    collection_stats::Entity::insert(stats)
+        .on_conflict(
+            OnConflict::column(collection_stats::Column::CollectionAddress)
+                .value(
+                    collection_stats::Column::Sales,
+                    increment(collection_stats::Column::Sales),
+                )
+                .value(
+                    collection_stats::Column::Volume,
+                    increment(collection_stats::Column::Volume),
+                )
+                .value(
+                    collection_stats::Column::VolumeOf1h,
+                    increment(collection_stats::Column::VolumeOf1h),
+                )
+                .value(
+                    collection_stats::Column::VolumeOf24h,
+                    increment(collection_stats::Column::VolumeOf24h),
+                )
+                .value(
+                    collection_stats::Column::VolumeOf7d,
+                    increment(collection_stats::Column::VolumeOf7d),
+                )
+                .value(
+                    collection_stats::Column::VolumeOf30d,
+                    increment(collection_stats::Column::VolumeOf30d),
+                )
+                .value(
+                    collection_stats::Column::UpdatedAt,
+                    Expr::current_timestamp(),
+                )
+                .to_owned(),
+        )
+        .exec(tx)
+        .await?;
+
+    Ok(())
+}
+
+// listing stats are recomputed from the listings of the collection only, which stays cheap at any volume
+pub async fn refresh_listing_stats(
+    tx: &DatabaseTransaction,
+    collection_address: &str,
+) -> Result<(), DbErr> {
+    let collection = Expr::val(collection_address).into();
+
+    let insert = Query::insert()
+        .into_table(collection_stats::Entity)
+        .columns([
+            collection_stats::Column::CollectionAddress,
+            collection_stats::Column::Listed,
+            collection_stats::Column::FloorPrice,
+            collection_stats::Column::CeilingPrice,
+        ])
+        .values_panic([
+            collection_address.into(),
+            listed(&collection),
+            floor_price(&collection),
+            ceiling_price(&collection),
+        ])
+        .on_conflict(
+            OnConflict::column(collection_stats::Column::CollectionAddress)
+                .update_columns([
+                    collection_stats::Column::Listed,
+                    collection_stats::Column::FloorPrice,
+                    collection_stats::Column::CeilingPrice,
+                ])
+                .value(
+                    collection_stats::Column::UpdatedAt,
+                    Expr::current_timestamp(),
+                )
+                .to_owned(),
+        )
+        .to_owned();
+
+    tx.execute(tx.get_database_backend().build(&insert)).await?;
+
+    Ok(())
+}
+
+pub async fn create_if_not_exist(
+    db: &DatabaseConnection,
+    collection_address: &str,
+) -> Result<(), DbErr> {
+    let stats = collection_stats::ActiveModel {
+        collection_address: Set(collection_address.to_owned()),
+        ..Default::default()
+    };
+
+    collection_stats::Entity::insert(stats)
+        .on_conflict(
+            OnConflict::column(collection_stats::Column::CollectionAddress)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec(db)
+        .await
+        .map(|_| ())
+        .or_else(|error| {
+            if let DbErr::RecordNotInserted = error {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
+}
+
+// only collections traded in the last 30 days or still listed can have a stale window or an expired listing
+pub async fn refresh_rolling_stats(db: &DatabaseConnection) -> Result<u64, DbErr> {
+    let collection = Expr::col((
+        collection_stats::Entity,
+        collection_stats::Column::CollectionAddress,
+    ))
+    .into();
+
+    let now = Utc::now();
+
+    let update = Query::update()
+        .table(collection_stats::Entity)
+        .value(
+            collection_stats::Column::VolumeOf1h,
+            volume_since(&collection, Some(now - Duration::hours(1))),
+        )
+        .value(
+            collection_stats::Column::VolumeOf24h,
+            volume_since(&collection, Some(now - Duration::days(1))),
+        )
+        .value(
+            collection_stats::Column::VolumeOf7d,
+            volume_since(&collection, Some(now - Duration::days(7))),
+        )
+        .value(
+            collection_stats::Column::VolumeOf30d,
+            volume_since(&collection, Some(now - Duration::days(30))),
+        )
+        .value(collection_stats::Column::Listed, listed(&collection))
+        .value(
+            collection_stats::Column::FloorPrice,
+            floor_price(&collection),
+        )
+        .value(
+            collection_stats::Column::CeilingPrice,
+            ceiling_price(&collection),
+        )
+        .value(
+            collection_stats::Column::UpdatedAt,
+            Expr::current_timestamp(),
+        )
+        .and_where(
+            collection_stats::Column::VolumeOf30d
+                .gt(Decimal::ZERO)
+                .or(collection_stats::Column::Listed.gt(0)),
+        )
+        .to_owned();
+
+    let result = db.execute(db.get_database_backend().build(&update)).await?;
+
+    Ok(result.rows_affected())
+}
+
+// recomputes every stat of every collection from scratch, for repair only since it scans every sale
+pub async fn rebuild(db: &DatabaseConnection) -> Result<u64, DbErr> {
+    let collection = Expr::col((collection::Entity, collection::Column::Address)).into();
+
+    let now = Utc::now();
+
+    let stats = Query::select()
+        .column((collection::Entity, collection::Column::Address))
+        .expr(listed(&collection))
+        .expr(floor_price(&collection))
+        .expr(ceiling_price(&collection))
+        .expr(sales(&collection))
+        .expr(volume_since(&collection, None))
+        .expr(volume_since(&collection, Some(now - Duration::hours(1))))
+        .expr(volume_since(&collection, Some(now - Duration::days(1))))
+        .expr(volume_since(&collection, Some(now - Duration::days(7))))
+        .expr(volume_since(&collection, Some(now - Duration::days(30))))
+        .from(collection::Entity)
+        .to_owned();
+
+    let columns = [
+        collection_stats::Column::Listed,
+        collection_stats::Column::FloorPrice,
+        collection_stats::Column::CeilingPrice,
+        collection_stats::Column::Sales,
+        collection_stats::Column::Volume,
+        collection_stats::Column::VolumeOf1h,
+        collection_stats::Column::VolumeOf24h,
+        collection_stats::Column::VolumeOf7d,
+        collection_stats::Column::VolumeOf30d,
+    ];
+
+    let insert = Query::insert()
+        .into_table(collection_stats::Entity)
+        .columns(
+            [collection_stats::Column::CollectionAddress]
+                .into_iter()
+                .chain(columns),
+        )
+        .select_from(stats)
+        .map_err(|e| DbErr::Custom(e.to_string()))?
+        .on_conflict(
+            OnConflict::column(collection_stats::Column::CollectionAddress)
+                .update_columns(columns)
+                .value(
+                    collection_stats::Column::UpdatedAt,
+                    Expr::current_timestamp(),
+                )
+                .to_owned(),
+        )
+        .to_owned();
+
+    let result = db.execute(db.get_database_backend().build(&insert)).await?;
+
+    Ok(result.rows_affected())
+}
+
+fn listed(collection: &SimpleExpr) -> SimpleExpr {
+    listing_stat(
+        collection,
+        Expr::col((listing_nft::Entity, listing_nft::Column::Id)).count(),
+    )
+}
+
+fn floor_price(collection: &SimpleExpr) -> SimpleExpr {
+    listing_stat(
+        collection,
+        Func::coalesce([
+            Expr::col((listing_nft::Entity, listing_nft::Column::Price)).min(),
+            Expr::val(Decimal::ZERO).into(),
+        ])
+        .into(),
+    )
+}
+
+fn ceiling_price(collection: &SimpleExpr) -> SimpleExpr {
+    listing_stat(
+        collection,
+        Func::coalesce([
+            Expr::col((listing_nft::Entity, listing_nft::Column::Price)).max(),
+            Expr::val(Decimal::ZERO).into(),
+        ])
+        .into(),
+    )
+}
+
+fn sales(collection: &SimpleExpr) -> SimpleExpr {
+    sale_stat(
+        collection,
+        Expr::col((transaction::Entity, transaction::Column::Id)).count(),
+        None,
+    )
+}
+
+fn volume_since(collection: &SimpleExpr, from: Option<DateTimeUtc>) -> SimpleExpr {
+    sale_stat(
+        collection,
+        Func::coalesce([
+            Expr::col((transaction::Entity, transaction::Column::Volume)).sum(),
+            Expr::val(Decimal::ZERO).into(),
+        ])
+        .into(),
+        from,
+    )
+}
+
+fn listing_stat(collection: &SimpleExpr, stat: SimpleExpr) -> SimpleExpr {
+    let query = Query::select()
+        .expr(stat)
+        .from(listing_nft::Entity)
+        .and_where(
+            Expr::col((listing_nft::Entity, listing_nft::Column::CollectionAddress))
+                .eq(collection.clone()),
+        )
+        .cond_where(
+            listing_nft::Column::ExpirationTime.is_null().or(Expr::col((
+                listing_nft::Entity,
+                listing_nft::Column::ExpirationTime,
+            ))
+            .gt(Expr::cust("EXTRACT(epoch FROM NOW())"))),
+        )
+        .to_owned();
+
+    SimpleExpr::SubQuery(None, Box::new(query.into_sub_query_statement()))
+}
+
+fn sale_stat(collection: &SimpleExpr, stat: SimpleExpr, from: Option<DateTimeUtc>) -> SimpleExpr {
+    let mut query = Query::select()
+        .expr(stat)
+        .from(transaction::Entity)
+        .and_where(
+            Expr::col((transaction::Entity, transaction::Column::CollectionAddress))
+                .eq(collection.clone()),
+        )
+        .to_owned();
+
+    if let Some(from) = from {
+        query.and_where(Expr::col((transaction::Entity, transaction::Column::Date)).gt(from));
+    }
+
+    SimpleExpr::SubQuery(None, Box::new(query.into_sub_query_statement()))
+}
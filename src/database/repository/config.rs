@@ -0,0 +1,27 @@
+use crate::database::entity::config;
+use sea_orm::{sea_query::OnConflict, DatabaseConnection, DbErr, EntityTrait, Set};
+
+pub async fn find_by_key(db: &DatabaseConnection, key: &str) -> Result<Option<String>, DbErr> {
+    config::Entity::find_by_id(key)
+        .one(db)
+        .await
+        .map(|config| config.map(|config| config.value))
+}
+
+pub async fn upsert(db: &DatabaseConnection, key: &str, value: String) -> Result<(), DbErr> {
+    let config = config::ActiveModel {
+        key: Set(key.to_owned()),
+        value: Set(value),
+    };
+
+    config::Entity::insert(config)
+        .on_conflict(
+            OnConflict::column(config::Column::Key)
+                .update_column(config::Column::Value)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
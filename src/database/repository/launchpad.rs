@@ -0,0 +1,243 @@
+use crate::database::{
+    entity::{launchpad, launchpad_mint, launchpad_phase},
+    model::{Count, WalletMint},
+};
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sea_query::{Expr, OnConflict},
+    ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+
+pub async fn find_by_collection_address(
+    db: &DatabaseConnection,
+    collection_address: &str,
+) -> Result<Option<launchpad::Model>, DbErr> {
+    launchpad::Entity::find_by_id(collection_address)
+        .one(db)
+        .await
+}
+
+pub async fn find_phases(
+    db: &DatabaseConnection,
+    collection_address: &str,
+) -> Result<Vec<launchpad_phase::Model>, DbErr> {
+    launchpad_phase::Entity::find()
+        .filter(launchpad_phase::Column::CollectionAddress.eq(collection_address))
+        .order_by_asc(launchpad_phase::Column::StartDate)
+        .all(db)
+        .await
+}
+
+pub async fn count_minters(
+    db: &DatabaseConnection,
+    collection_address: &str,
+) -> Result<i64, DbErr> {
+    let total = launchpad_mint::Entity::find()
+        .select_only()
+        .column_as(
+            Expr::col(launchpad_mint::Column::WalletAddress).count_distinct(),
+            "count",
+        )
+        .filter(launchpad_mint::Column::CollectionAddress.eq(collection_address))
+        .into_model::<Count>()
+        .one(db)
+        .await?
+        .unwrap_or_default();
+
+    Ok(total.count)
+}
+
+pub async fn find_wallet_mints(
+    db: &DatabaseConnection,
+    collection_address: &str,
+    wallet_address: &str,
+) -> Result<Vec<WalletMint>, DbErr> {
+    launchpad_mint::Entity::find()
+        .select_only()
+        .column(launchpad_mint::Column::Phase)
+        .column_as(launchpad_mint::Column::Id.count(), "minted")
+        .filter(launchpad_mint::Column::CollectionAddress.eq(collection_address))
+        .filter(launchpad_mint::Column::WalletAddress.eq(wallet_address))
+        .group_by(launchpad_mint::Column::Phase)
+        .into_model::<WalletMint>()
+        .all(db)
+        .await
+}
+
+pub async fn create_if_not_exist(
+    db: &DatabaseConnection,
+    params: CreateLaunchpadParams,
+) -> Result<(), DbErr> {
+    launchpad::Entity::insert(params.into_active_model())
+        .on_conflict(
+            OnConflict::column(launchpad::Column::CollectionAddress)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec(db)
+        .await
+        .map(|_| ())
+        .or_else(|error| {
+            if let DbErr::RecordNotInserted = error {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
+}
+
+pub async fn upsert(db: &DatabaseConnection, params: CreateLaunchpadParams) -> Result<(), DbErr> {
+    launchpad::Entity::insert(params.into_active_model())
+        .on_conflict(
+            OnConflict::column(launchpad::Column::CollectionAddress)
+                .update_columns([
+                    launchpad::Column::ContractAddress,
+                    launchpad::Column::MaxSupply,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_phase(
+    db: &DatabaseConnection,
+    params: UpsertLaunchpadPhaseParams,
+) -> Result<(), DbErr> {
+    let phase = launchpad_phase::ActiveModel {
+        collection_address: Set(params.collection_address),
+        name: Set(params.name),
+        start_date: Set(params.start_date.into()),
+        end_date: Set(params.end_date.map(Into::into)),
+        price: Set(params.price),
+        denom: Set(params.denom),
+        max_per_wallet: Set(params.max_per_wallet),
+        ..Default::default()
+    };
+
+    launchpad_phase::Entity::insert(phase)
+        .on_conflict(
+            OnConflict::columns([
+                launchpad_phase::Column::CollectionAddress,
+                launchpad_phase::Column::Name,
+            ])
+            .update_columns([
+                launchpad_phase::Column::StartDate,
+                launchpad_phase::Column::EndDate,
+                launchpad_phase::Column::Price,
+                launchpad_phase::Column::Denom,
+                launchpad_phase::Column::MaxPerWallet,
+            ])
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+// counters only move when the mint is seen for the first time, so replaying a tx is harmless
+pub async fn create_mint(
+    tx: &DatabaseTransaction,
+    params: CreateLaunchpadMintParams,
+) -> Result<(), DbErr> {
+    let mint = launchpad_mint::ActiveModel {
+        tx_hash: Set(params.tx_hash),
+        date: Set(params.date.into()),
+        collection_address: Set(params.collection_address.to_owned()),
+        phase: Set(params.phase.to_owned()),
+        wallet_address: Set(params.wallet_address),
+        token_id: Set(params.token_id),
+        nft_id: Set(params.nft_id),
+        price: Set(params.price),
+        denom: Set(params.denom),
+        ..Default::default()
+    };
+
+    let result = launchpad_mint::Entity::insert(mint)
+        .on_conflict(
+            OnConflict::columns([
+                launchpad_mint::Column::CollectionAddress,
+                launchpad_mint::Column::TokenId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec(tx)
+        .await;
+
+    match result {
+        Ok(_) => {}
+        Err(DbErr::RecordNotInserted) => return Ok(()),
+        Err(error) => return Err(error),
+    }
+
+    launchpad::Entity::update_many()
+        .col_expr(
+            launchpad::Column::TotalMinted,
+            Expr::col(launchpad::Column::TotalMinted).add(1),
+        )
+        .col_expr(
+            launchpad::Column::MintRevenue,
+            Expr::col(launchpad::Column::MintRevenue).add(params.price),
+        )
+        .filter(launchpad::Column::CollectionAddress.eq(&params.collection_address))
+        .exec(tx)
+        .await?;
+
+    launchpad_phase::Entity::update_many()
+        .col_expr(
+            launchpad_phase::Column::Minted,
+            Expr::col(launchpad_phase::Column::Minted).add(1),
+        )
+        .filter(launchpad_phase::Column::CollectionAddress.eq(params.collection_address))
+        .filter(launchpad_phase::Column::Name.eq(params.phase))
+        .exec(tx)
+        .await?;
+
+    Ok(())
+}
+
+pub struct CreateLaunchpadParams {
+    pub collection_address: String,
+    pub contract_address: String,
+    pub max_supply: Option<i32>,
+    pub date: DateTimeUtc,
+}
+
+impl CreateLaunchpadParams {
+    fn into_active_model(self) -> launchpad::ActiveModel {
+        launchpad::ActiveModel {
+            collection_address: Set(self.collection_address),
+            contract_address: Set(self.contract_address),
+            max_supply: Set(self.max_supply),
+            created_date: Set(self.date.into()),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct UpsertLaunchpadPhaseParams {
+    pub collection_address: String,
+    pub name: String,
+    pub start_date: DateTimeUtc,
+    pub end_date: Option<DateTimeUtc>,
+    pub price: Decimal,
+    pub denom: String,
+    pub max_per_wallet: Option<i32>,
+}
+
+pub struct CreateLaunchpadMintParams {
+    pub tx_hash: String,
+    pub date: DateTimeUtc,
+    pub collection_address: String,
+    pub phase: String,
+    pub wallet_address: String,
+    pub token_id: String,
+    pub nft_id: i32,
+    pub price: Decimal,
+    pub denom: String,
+}
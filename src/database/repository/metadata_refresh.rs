@@ -0,0 +1,27 @@
+use crate::database::entity::metadata_refresh;
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait, Set};
+use serde_json::Value;
+
+pub async fn create(
+    db: &DatabaseConnection,
+    params: CreateMetadataRefreshParams,
+) -> Result<(), DbErr> {
+    let metadata_refresh = metadata_refresh::ActiveModel {
+        collection_address: Set(params.collection_address),
+        token_id: Set(params.token_id),
+        changes: Set(params.changes),
+        ..Default::default()
+    };
+
+    metadata_refresh::Entity::insert(metadata_refresh)
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub struct CreateMetadataRefreshParams {
+    pub collection_address: String,
+    pub token_id: Option<String>,
+    pub changes: Value,
+}
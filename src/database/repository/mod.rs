@@ -1,6 +1,17 @@
 pub mod collection;
+pub mod collection_offer;
+pub mod collection_snapshot;
+pub mod collection_stats;
+pub mod config;
+pub mod launchpad;
+pub mod metadata_refresh;
 pub mod nft;
 pub mod nft_activity;
+pub mod nft_offer;
+pub mod nft_rarity;
+pub mod search;
 pub mod tracing;
 pub mod transaction;
+pub mod user;
 pub mod user_point;
+pub mod wallet_link;
@@ -1,16 +1,21 @@
 use crate::{
-    database::entity::{
-        listing_nft, nft, nft_trait,
-        sea_orm_active_enums::{Marketplace, SaleType},
+    database::{
+        entity::{
+            collection, collection_view, listing_nft, nft, nft_bidding, nft_rarity, nft_trait,
+            sea_orm_active_enums::{Marketplace, RarityMethod, SaleType},
+        },
+        model::{Count, ListedNft, OwnedCollection, TraitCount, TraitListing, TraitRarity},
     },
-    service::NftAttribute,
+    server::{api::collection::ListedNftSortBy, deserialization::SortDirection},
+    service::{NftAttribute, NftTrait},
 };
 use sea_orm::{
     prelude::{DateTimeUtc, Decimal},
-    sea_query::OnConflict,
-    ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, QueryFilter, Set,
-    TransactionTrait,
+    sea_query::{Alias, Expr, IntoCondition, NullOrdering, OnConflict, Query, SimpleExpr},
+    ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, JoinType,
+    QueryFilter, QueryOrder, QuerySelect, RelationTrait, Select, Set, TransactionTrait,
 };
+use std::collections::{BTreeMap, BTreeSet};
 
 pub async fn find_by_address_and_token_id(
     db: &DatabaseConnection,
@@ -34,6 +39,89 @@ pub async fn find_listing_by_nft_id(
         .await
 }
 
+// expired listings stay in the table until someone delists them, they are not buyable anymore
+pub async fn find_active_listing_by_nft_id(
+    db: &DatabaseConnection,
+    nft_id: i32,
+) -> Result<Option<listing_nft::Model>, DbErr> {
+    listing_nft::Entity::find()
+        .filter(listing_nft::Column::NftId.eq(nft_id))
+        .filter(active_listing_condition())
+        .one(db)
+        .await
+}
+
+pub async fn find_biddings_by_listing_id(
+    db: &DatabaseConnection,
+    listing_id: i32,
+) -> Result<Vec<nft_bidding::Model>, DbErr> {
+    nft_bidding::Entity::find()
+        .filter(nft_bidding::Column::ListingId.eq(listing_id))
+        .order_by_desc(nft_bidding::Column::Price)
+        .order_by_asc(nft_bidding::Column::CreatedDate)
+        .all(db)
+        .await
+}
+
+pub async fn count_by_token_address(
+    db: &DatabaseConnection,
+    token_address: &str,
+) -> Result<i64, DbErr> {
+    let total = nft::Entity::find()
+        .select_only()
+        .column_as(nft::Column::Id.count(), "count")
+        .filter(nft::Column::TokenAddress.eq(token_address))
+        .into_model::<Count>()
+        .one(db)
+        .await?
+        .unwrap_or_default();
+
+    Ok(total.count)
+}
+
+// count is how many nfts of the collection share the same attribute and value
+pub async fn find_traits_with_rarity(
+    db: &DatabaseConnection,
+    nft_id: i32,
+    token_address: &str,
+) -> Result<Vec<TraitRarity>, DbErr> {
+    let other = Alias::new("other");
+
+    let count = Query::select()
+        .expr(Expr::col((other.clone(), nft_trait::Column::Id)).count())
+        .from_as(nft_trait::Entity, other.clone())
+        .inner_join(
+            nft::Entity,
+            Expr::col((nft::Entity, nft::Column::Id))
+                .equals((other.clone(), nft_trait::Column::NftId)),
+        )
+        .and_where(Expr::col((nft::Entity, nft::Column::TokenAddress)).eq(token_address))
+        .and_where(
+            Expr::col((other.clone(), nft_trait::Column::Attribute))
+                .equals((nft_trait::Entity, nft_trait::Column::Attribute)),
+        )
+        .and_where(
+            Expr::col((other, nft_trait::Column::Value))
+                .equals((nft_trait::Entity, nft_trait::Column::Value)),
+        )
+        .to_owned();
+
+    nft_trait::Entity::find()
+        .select_only()
+        .column(nft_trait::Column::Attribute)
+        .column(nft_trait::Column::Value)
+        .column(nft_trait::Column::DisplayType)
+        .column_as(
+            SimpleExpr::SubQuery(None, Box::new(count.into_sub_query_statement())),
+            "count",
+        )
+        .filter(nft_trait::Column::NftId.eq(nft_id))
+        .order_by_asc(nft_trait::Column::Attribute)
+        .into_model::<TraitRarity>()
+        .all(db)
+        .await
+}
+
 pub async fn update_owner(
     db: &DatabaseConnection,
     token_address: &str,
@@ -95,33 +183,102 @@ pub async fn create(db: &DatabaseConnection, params: CreateNftParams) -> Result<
         }
     };
 
-    let traits = params.traits.unwrap_or_default().into_iter().map(
-        |NftAttribute {
-             trait_type,
-             r#type,
-             value,
-             display_type,
-         }| nft_trait::ActiveModel {
-            nft_id: Set(nft_id),
-            attribute: Set(trait_type.unwrap_or(r#type.unwrap_or("unknown".to_string()))),
-            display_type: Set(display_type.map(|v| v.to_string())),
-            value: Set(value
-                .map(|v| v.to_string())
-                .unwrap_or("unknown".to_string())),
-            ..Default::default()
-        },
-    );
+    let traits: Vec<NftTrait> = params
+        .traits
+        .unwrap_or_default()
+        .into_iter()
+        .map(NftTrait::from)
+        .collect();
 
-    nft_trait::Entity::insert_many(traits)
+    nft_trait::Entity::insert_many(to_trait_models(nft_id, traits))
         .on_empty_do_nothing()
         .exec(&txn)
         .await?;
 
+    // a new nft changes the supply every trait frequency is relative to
+    collection::Entity::update_many()
+        .col_expr(collection::Column::RarityOutdated, Expr::value(true))
+        .filter(collection::Column::Address.eq(&params.token_address))
+        .exec(&txn)
+        .await?;
+
     txn.commit().await?;
 
     Ok(nft_id)
 }
 
+pub async fn find_traits(db: &DatabaseConnection, nft_id: i32) -> Result<Vec<NftTrait>, DbErr> {
+    let traits = nft_trait::Entity::find()
+        .filter(nft_trait::Column::NftId.eq(nft_id))
+        .all(db)
+        .await?;
+
+    Ok(traits
+        .into_iter()
+        .map(|nft_trait| NftTrait {
+            attribute: nft_trait.attribute,
+            value: nft_trait.value,
+            display_type: nft_trait.display_type,
+        })
+        .collect())
+}
+
+pub async fn find_token_ids(
+    db: &DatabaseConnection,
+    token_address: &str,
+) -> Result<Vec<String>, DbErr> {
+    nft::Entity::find()
+        .select_only()
+        .column(nft::Column::TokenId)
+        .filter(nft::Column::TokenAddress.eq(token_address))
+        .order_by_asc(nft::Column::Id)
+        .into_tuple::<String>()
+        .all(db)
+        .await
+}
+
+// traits are only replaced when given, so a refresh that only renames the nft keeps its rarity
+pub async fn update_metadata(
+    db: &DatabaseConnection,
+    params: UpdateNftMetadataParams,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    let nft = nft::ActiveModel {
+        token_uri: Set(params.token_uri),
+        name: Set(params.name),
+        image: Set(params.image),
+        description: Set(params.description),
+        ..Default::default()
+    };
+
+    nft::Entity::update_many()
+        .set(nft)
+        .filter(nft::Column::Id.eq(params.nft_id))
+        .exec(&txn)
+        .await?;
+
+    if let Some(traits) = params.traits {
+        nft_trait::Entity::delete_many()
+            .filter(nft_trait::Column::NftId.eq(params.nft_id))
+            .exec(&txn)
+            .await?;
+
+        nft_trait::Entity::insert_many(to_trait_models(params.nft_id, traits))
+            .on_empty_do_nothing()
+            .exec(&txn)
+            .await?;
+
+        collection::Entity::update_many()
+            .col_expr(collection::Column::RarityOutdated, Expr::value(true))
+            .filter(collection::Column::Address.eq(&params.token_address))
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await
+}
+
 pub async fn create_pallet_listing(
     tx: &DatabaseTransaction,
     params: CreatePalletListingParams,
@@ -178,6 +335,332 @@ pub async fn delete_listing_if_exist(tx: &DatabaseTransaction, nft_id: i32) -> R
     Ok(())
 }
 
+pub async fn find_listed_nfts(
+    db: &DatabaseConnection,
+    params: FindListedNftsParams,
+) -> Result<(Vec<ListedNft>, i64), DbErr> {
+    let skip = (params.page - 1) * params.limit as u64;
+    let condition = listed_nft_condition(&params.filter);
+
+    let sort_by: SimpleExpr = match params.sort_by {
+        ListedNftSortBy::Price => {
+            Expr::col((listing_nft::Entity, listing_nft::Column::Price)).into()
+        }
+        ListedNftSortBy::ListedDate => {
+            Expr::col((listing_nft::Entity, listing_nft::Column::CreatedDate)).into()
+        }
+        ListedNftSortBy::Rarity => {
+            Expr::col((nft_rarity::Entity, nft_rarity::Column::Score)).into()
+        }
+    };
+
+    let mut query = select_listed_nfts(params.rarity_method).filter(condition.clone());
+
+    // nfts are only ranked once the schedule caught up with their collection
+    QueryOrder::query(&mut query).order_by_expr_with_nulls(
+        sort_by,
+        params.sort_direction.into_order(),
+        NullOrdering::Last,
+    );
+
+    let nfts = query
+        .order_by_asc(listing_nft::Column::Id)
+        .limit(params.limit as u64)
+        .offset(skip)
+        .into_model::<ListedNft>()
+        .all(db)
+        .await?;
+
+    let total = listing_nft::Entity::find()
+        .select_only()
+        .column_as(listing_nft::Column::Id.count(), "count")
+        .inner_join(nft::Entity)
+        .filter(condition)
+        .into_model::<Count>()
+        .one(db)
+        .await?
+        .unwrap_or_default();
+
+    Ok((nfts, total.count))
+}
+
+// listed or not, filtered the same way as `find_trait_listings` so the counts agree with the listings
+pub async fn find_trait_counts(
+    db: &DatabaseConnection,
+    token_address: &str,
+    traits: &[(String, String)],
+) -> Result<Vec<TraitCount>, DbErr> {
+    let filtered_attributes: BTreeSet<&str> = traits
+        .iter()
+        .map(|(attribute, _)| attribute.as_str())
+        .collect();
+
+    let mut counts = select_trait_counts(token_address)
+        .filter(trait_filter_condition(traits))
+        .filter(nft_trait::Column::Attribute.is_not_in(filtered_attributes.iter().copied()))
+        .into_model::<TraitCount>()
+        .all(db)
+        .await?;
+
+    for attribute in filtered_attributes {
+        counts.extend(
+            select_trait_counts(token_address)
+                .filter(trait_filter_condition(&other_trait_filters(
+                    traits, attribute,
+                )))
+                .filter(nft_trait::Column::Attribute.eq(attribute))
+                .into_model::<TraitCount>()
+                .all(db)
+                .await?,
+        );
+    }
+
+    Ok(counts)
+}
+
+// the values of a filtered attribute ignore the filter on that attribute, so picking one value
+// does not hide the others as its values are alternatives
+pub async fn find_trait_listings(
+    db: &DatabaseConnection,
+    filter: &ListedNftFilter,
+) -> Result<Vec<TraitListing>, DbErr> {
+    let filtered_attributes: BTreeSet<&str> = filter
+        .traits
+        .iter()
+        .map(|(attribute, _)| attribute.as_str())
+        .collect();
+
+    let mut listings = select_trait_listings()
+        .filter(listed_nft_condition(filter))
+        .filter(nft_trait::Column::Attribute.is_not_in(filtered_attributes.iter().copied()))
+        .into_model::<TraitListing>()
+        .all(db)
+        .await?;
+
+    for attribute in filtered_attributes {
+        let others = ListedNftFilter {
+            traits: other_trait_filters(&filter.traits, attribute),
+            ..filter.clone()
+        };
+
+        listings.extend(
+            select_trait_listings()
+                .filter(listed_nft_condition(&others))
+                .filter(nft_trait::Column::Attribute.eq(attribute))
+                .into_model::<TraitListing>()
+                .all(db)
+                .await?,
+        );
+    }
+
+    Ok(listings)
+}
+
+pub async fn find_listings_by_sellers(
+    db: &DatabaseConnection,
+    sellers: &[String],
+    rarity_method: RarityMethod,
+) -> Result<Vec<ListedNft>, DbErr> {
+    select_listed_nfts(rarity_method)
+        .filter(listing_nft::Column::SellerAddress.is_in(sellers))
+        .filter(active_listing_condition())
+        .order_by_desc(listing_nft::Column::CreatedDate)
+        .into_model::<ListedNft>()
+        .all(db)
+        .await
+}
+
+pub async fn find_by_owners(
+    db: &DatabaseConnection,
+    owners: &[String],
+) -> Result<Vec<nft::Model>, DbErr> {
+    nft::Entity::find()
+        .filter(nft::Column::OwnerAddress.is_in(owners))
+        .order_by_asc(nft::Column::TokenAddress)
+        .order_by_asc(nft::Column::Id)
+        .all(db)
+        .await
+}
+
+// the value of a holding is estimated at the collection floor, most valuable holdings first
+pub async fn find_owned_collections(
+    db: &DatabaseConnection,
+    owners: &[String],
+) -> Result<Vec<OwnedCollection>, DbErr> {
+    let floor_price = Expr::col((collection_view::Entity, collection_view::Column::FloorPrice));
+
+    nft::Entity::find()
+        .select_only()
+        .column(collection_view::Column::Address)
+        .column(collection_view::Column::Name)
+        .column(collection_view::Column::Image)
+        .column(collection_view::Column::FloorPrice)
+        .column_as(nft::Column::Id.count(), "owned")
+        .column_as(
+            floor_price.clone().mul(nft::Column::Id.count()),
+            "estimated_value",
+        )
+        .join(
+            JoinType::InnerJoin,
+            nft::Entity::belongs_to(collection_view::Entity)
+                .from(nft::Column::TokenAddress)
+                .to(collection_view::Column::Address)
+                .into(),
+        )
+        .filter(nft::Column::OwnerAddress.is_in(owners))
+        .group_by(collection_view::Column::Address)
+        .group_by(collection_view::Column::Name)
+        .group_by(collection_view::Column::Image)
+        .group_by(collection_view::Column::FloorPrice)
+        .order_by_desc(floor_price.mul(nft::Column::Id.count()))
+        .into_model::<OwnedCollection>()
+        .all(db)
+        .await
+}
+
+fn select_trait_counts(token_address: &str) -> Select<nft_trait::Entity> {
+    nft_trait::Entity::find()
+        .select_only()
+        .column(nft_trait::Column::Attribute)
+        .column(nft_trait::Column::Value)
+        .column_as(nft_trait::Column::Id.count(), "count")
+        .inner_join(nft::Entity)
+        .filter(nft::Column::TokenAddress.eq(token_address))
+        .group_by(nft_trait::Column::Attribute)
+        .group_by(nft_trait::Column::Value)
+}
+
+fn select_trait_listings() -> Select<listing_nft::Entity> {
+    listing_nft::Entity::find()
+        .select_only()
+        .column(nft_trait::Column::Attribute)
+        .column(nft_trait::Column::Value)
+        .column_as(listing_nft::Column::Id.count(), "listed")
+        .column_as(listing_nft::Column::Price.min(), "floor_price")
+        .inner_join(nft::Entity)
+        .join(JoinType::InnerJoin, nft::Relation::NftTrait.def())
+        .group_by(nft_trait::Column::Attribute)
+        .group_by(nft_trait::Column::Value)
+}
+
+fn select_listed_nfts(rarity_method: RarityMethod) -> Select<listing_nft::Entity> {
+    listing_nft::Entity::find()
+        .select_only()
+        .column_as(nft::Column::Id, "nft_id")
+        .column(nft::Column::TokenAddress)
+        .column(nft::Column::TokenId)
+        .column(nft::Column::Name)
+        .column(nft::Column::Image)
+        .column(listing_nft::Column::Price)
+        .column(listing_nft::Column::Denom)
+        .column(listing_nft::Column::Market)
+        .column(listing_nft::Column::SaleType)
+        .column(listing_nft::Column::SellerAddress)
+        .column_as(listing_nft::Column::CreatedDate, "listed_date")
+        .column(listing_nft::Column::ExpirationTime)
+        .column_as(nft_rarity::Column::Rank, "rarity_rank")
+        .inner_join(nft::Entity)
+        .join(
+            JoinType::LeftJoin,
+            nft::Relation::NftRarity
+                .def()
+                .on_condition(move |_, rarity| {
+                    Expr::col((rarity, nft_rarity::Column::Method))
+                        .eq(rarity_method.to_owned())
+                        .into_condition()
+                }),
+        )
+}
+
+// values of the same attribute are alternatives, different attributes must all match
+fn listed_nft_condition(filter: &ListedNftFilter) -> Condition {
+    Condition::all()
+        .add(listing_nft::Column::CollectionAddress.eq(&filter.collection_address))
+        .add(active_listing_condition())
+        .add_option(
+            filter
+                .min_price
+                .map(|price| listing_nft::Column::Price.gte(price)),
+        )
+        .add_option(
+            filter
+                .max_price
+                .map(|price| listing_nft::Column::Price.lte(price)),
+        )
+        .add_option(
+            filter
+                .market
+                .to_owned()
+                .map(|market| listing_nft::Column::Market.eq(market)),
+        )
+        .add_option(
+            filter
+                .sale_type
+                .to_owned()
+                .map(|sale_type| listing_nft::Column::SaleType.eq(sale_type)),
+        )
+        .add(trait_filter_condition(&filter.traits))
+}
+
+// values of an attribute are alternatives, attributes must all match
+fn trait_filter_condition(traits: &[(String, String)]) -> Condition {
+    let mut values_by_attribute: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+
+    for (attribute, value) in traits {
+        values_by_attribute
+            .entry(attribute)
+            .or_default()
+            .push(value);
+    }
+
+    values_by_attribute
+        .into_iter()
+        .fold(Condition::all(), |condition, (attribute, values)| {
+            condition.add(
+                nft::Column::Id.in_subquery(
+                    Query::select()
+                        .column(nft_trait::Column::NftId)
+                        .from(nft_trait::Entity)
+                        .and_where(nft_trait::Column::Attribute.eq(attribute))
+                        .and_where(nft_trait::Column::Value.is_in(values))
+                        .to_owned(),
+                ),
+            )
+        })
+}
+
+// the values of a filtered attribute are counted against every other filter, not their own
+fn other_trait_filters(traits: &[(String, String)], attribute: &str) -> Vec<(String, String)> {
+    traits
+        .iter()
+        .filter(|(other, _)| other != attribute)
+        .cloned()
+        .collect()
+}
+
+fn to_trait_models(nft_id: i32, traits: Vec<NftTrait>) -> Vec<nft_trait::ActiveModel> {
+    traits
+        .into_iter()
+        .map(|nft_trait| nft_trait::ActiveModel {
+            nft_id: Set(nft_id),
+            attribute: Set(nft_trait.attribute),
+            value: Set(nft_trait.value),
+            display_type: Set(nft_trait.display_type),
+            ..Default::default()
+        })
+        .collect()
+}
+
+// expiration_time is a unix timestamp in seconds, listings without it never expire
+fn active_listing_condition() -> Condition {
+    Condition::any()
+        .add(listing_nft::Column::ExpirationTime.is_null())
+        .add(
+            Expr::col((listing_nft::Entity, listing_nft::Column::ExpirationTime))
+                .gt(Expr::cust("EXTRACT(epoch FROM NOW())")),
+        )
+}
+
 pub struct CreateNftParams {
     pub token_address: String,
     pub token_id: String,
@@ -189,6 +672,16 @@ pub struct CreateNftParams {
     pub owner_address: Option<String>,
 }
 
+pub struct UpdateNftMetadataParams {
+    pub nft_id: i32,
+    pub token_address: String,
+    pub token_uri: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub description: Option<String>,
+    pub traits: Option<Vec<NftTrait>>,
+}
+
 pub struct CreatePalletListingParams {
     pub nft_id: i32,
     pub collection_address: String,
@@ -199,3 +692,22 @@ pub struct CreatePalletListingParams {
     pub seller: String,
     pub expiration_time: Option<i32>,
 }
+
+#[derive(Clone)]
+pub struct ListedNftFilter {
+    pub collection_address: String,
+    pub traits: Vec<(String, String)>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub market: Option<Marketplace>,
+    pub sale_type: Option<SaleType>,
+}
+
+pub struct FindListedNftsParams {
+    pub filter: ListedNftFilter,
+    pub rarity_method: RarityMethod,
+    pub sort_by: ListedNftSortBy,
+    pub sort_direction: SortDirection,
+    pub page: u64,
+    pub limit: u8,
+}
@@ -1,10 +1,15 @@
-use crate::database::entity::{
-    nft_activity,
-    sea_orm_active_enums::{Marketplace, NftActivityKind},
+use crate::database::{
+    entity::{
+        nft, nft_activity,
+        sea_orm_active_enums::{Marketplace, NftActivityKind},
+    },
+    model::{Activity, Count, SalePrice},
 };
 use sea_orm::{
     prelude::{DateTimeUtc, Decimal},
-    DatabaseTransaction, DbErr, EntityTrait, Set,
+    sea_query::Expr,
+    ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select, Set,
 };
 
 pub async fn create(
@@ -30,6 +35,176 @@ pub async fn create(
     Ok(())
 }
 
+pub async fn find_by_nft_id(
+    db: &DatabaseConnection,
+    nft_id: i32,
+    page: u64,
+    limit: u8,
+) -> Result<(Vec<nft_activity::Model>, i64), DbErr> {
+    let skip = (page - 1) * limit as u64;
+
+    let activities = nft_activity::Entity::find()
+        .filter(nft_activity::Column::NftId.eq(nft_id))
+        .order_by_desc(nft_activity::Column::Date)
+        .order_by_desc(nft_activity::Column::Id)
+        .limit(limit as u64)
+        .offset(skip)
+        .all(db)
+        .await?;
+
+    let total = nft_activity::Entity::find()
+        .select_only()
+        .column_as(nft_activity::Column::Id.count(), "count")
+        .filter(nft_activity::Column::NftId.eq(nft_id))
+        .into_model::<Count>()
+        .one(db)
+        .await?
+        .unwrap_or_default();
+
+    Ok((activities, total.count))
+}
+
+// oldest first so the sales read as a price chart, the last one is the latest sale
+pub async fn find_sales_by_nft_id(
+    db: &DatabaseConnection,
+    nft_id: i32,
+) -> Result<Vec<SalePrice>, DbErr> {
+    nft_activity::Entity::find()
+        .select_only()
+        .column(nft_activity::Column::Price)
+        .column(nft_activity::Column::Denom)
+        .column(nft_activity::Column::Market)
+        .column(nft_activity::Column::Date)
+        .filter(nft_activity::Column::NftId.eq(nft_id))
+        .filter(nft_activity::Column::EventKind.eq(NftActivityKind::Sale))
+        .order_by_asc(nft_activity::Column::Date)
+        .order_by_asc(nft_activity::Column::Id)
+        .into_model::<SalePrice>()
+        .all(db)
+        .await
+}
+
+// newest first, the cursor is the (date, id) of the last activity of the previous page
+pub async fn find_activities(
+    db: &DatabaseConnection,
+    params: FindActivitiesParams,
+) -> Result<Vec<Activity>, DbErr> {
+    let cursor_condition = params.cursor.map(|(date, id)| {
+        Condition::any()
+            .add(Expr::col((nft_activity::Entity, nft_activity::Column::Date)).lt(date))
+            .add(
+                Condition::all()
+                    .add(Expr::col((nft_activity::Entity, nft_activity::Column::Date)).eq(date))
+                    .add(Expr::col((nft_activity::Entity, nft_activity::Column::Id)).lt(id)),
+            )
+    });
+
+    select_activities()
+        .filter(scope_condition(params.scope))
+        .filter(
+            Condition::all()
+                .add_option(cursor_condition)
+                .add_option(
+                    params
+                        .kind
+                        .map(|kind| nft_activity::Column::EventKind.eq(kind)),
+                )
+                .add_option(
+                    params
+                        .market
+                        .map(|market| nft_activity::Column::Market.eq(market)),
+                )
+                .add_option(
+                    params
+                        .min_price
+                        .map(|price| nft_activity::Column::Price.gte(price)),
+                )
+                .add_option(
+                    params
+                        .max_price
+                        .map(|price| nft_activity::Column::Price.lte(price)),
+                )
+                .add_option(
+                    params
+                        .from_date
+                        .map(|date| nft_activity::Column::Date.gte(date)),
+                )
+                .add_option(
+                    params
+                        .to_date
+                        .map(|date| nft_activity::Column::Date.lte(date)),
+                ),
+        )
+        .order_by_desc(nft_activity::Column::Date)
+        .order_by_desc(nft_activity::Column::Id)
+        .limit(params.limit)
+        .into_model::<Activity>()
+        .all(db)
+        .await
+}
+
+pub async fn find_activity_by_id(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<Option<Activity>, DbErr> {
+    select_activities()
+        .filter(nft_activity::Column::Id.eq(id))
+        .into_model::<Activity>()
+        .one(db)
+        .await
+}
+
+// oldest first, replays what a client missed since the last activity it has seen
+pub async fn find_activities_after(
+    db: &DatabaseConnection,
+    scope: ActivityScope,
+    after_id: i32,
+    limit: u64,
+) -> Result<Vec<Activity>, DbErr> {
+    select_activities()
+        .filter(scope_condition(scope))
+        .filter(nft_activity::Column::Id.gt(after_id))
+        .order_by_asc(nft_activity::Column::Id)
+        .limit(limit)
+        .into_model::<Activity>()
+        .all(db)
+        .await
+}
+
+fn select_activities() -> Select<nft_activity::Entity> {
+    nft_activity::Entity::find()
+        .select_only()
+        .column(nft_activity::Column::Id)
+        .column(nft_activity::Column::TxHash)
+        .column(nft_activity::Column::EventKind)
+        .column(nft_activity::Column::Market)
+        .column(nft_activity::Column::Price)
+        .column(nft_activity::Column::Denom)
+        .column(nft_activity::Column::SellerAddress)
+        .column(nft_activity::Column::BuyerAddress)
+        .column(nft_activity::Column::Date)
+        .column(nft::Column::TokenAddress)
+        .column(nft::Column::TokenId)
+        .column(nft::Column::Name)
+        .column(nft::Column::Image)
+        .inner_join(nft::Entity)
+}
+
+fn scope_condition(scope: ActivityScope) -> Condition {
+    match scope {
+        ActivityScope::All => Condition::all(),
+        ActivityScope::Collection(collection_address) => {
+            Condition::all().add(nft::Column::TokenAddress.eq(collection_address))
+        }
+        ActivityScope::Nft(collection_address, token_id) => Condition::all()
+            .add(nft::Column::TokenAddress.eq(collection_address))
+            .add(nft::Column::TokenId.eq(token_id)),
+        ActivityScope::Wallet(wallet_addresses) => Condition::any()
+            .add(nft_activity::Column::SellerAddress.is_in(wallet_addresses.to_owned()))
+            .add(nft_activity::Column::BuyerAddress.is_in(wallet_addresses)),
+    }
+}
+
 pub struct CreateNftActivityParams {
     pub denom: String,
     pub metadata: serde_json::Value,
@@ -42,3 +217,24 @@ pub struct CreateNftActivityParams {
     pub created_date: DateTimeUtc,
     pub marketplace: Marketplace,
 }
+
+#[derive(Clone)]
+pub enum ActivityScope {
+    All,
+    Collection(String),
+    Nft(String, String),
+    // every linked address of the wallet
+    Wallet(Vec<String>),
+}
+
+pub struct FindActivitiesParams {
+    pub scope: ActivityScope,
+    pub kind: Option<NftActivityKind>,
+    pub market: Option<Marketplace>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub from_date: Option<DateTimeUtc>,
+    pub to_date: Option<DateTimeUtc>,
+    pub cursor: Option<(DateTimeUtc, i32)>,
+    pub limit: u64,
+}
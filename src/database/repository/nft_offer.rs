@@ -0,0 +1,77 @@
+use crate::database::{
+    entity::{nft, nft_offer},
+    model::NftOfferDetail,
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Select,
+};
+
+pub async fn find_active_by_nft_id(
+    db: &DatabaseConnection,
+    nft_id: i32,
+) -> Result<Vec<nft_offer::Model>, DbErr> {
+    nft_offer::Entity::find()
+        .filter(nft_offer::Column::NftId.eq(nft_id))
+        .filter(active_offer_condition())
+        .order_by_desc(nft_offer::Column::Price)
+        .all(db)
+        .await
+}
+
+pub async fn find_active_by_buyers(
+    db: &DatabaseConnection,
+    buyers: &[String],
+) -> Result<Vec<NftOfferDetail>, DbErr> {
+    select_offer_details()
+        .filter(nft_offer::Column::BuyerAddress.is_in(buyers))
+        .filter(active_offer_condition())
+        .order_by_desc(nft_offer::Column::CreatedDate)
+        .into_model::<NftOfferDetail>()
+        .all(db)
+        .await
+}
+
+// offers on the nfts the wallet currently holds, so only the owner is able to accept them
+pub async fn find_active_by_owners(
+    db: &DatabaseConnection,
+    owners: &[String],
+) -> Result<Vec<NftOfferDetail>, DbErr> {
+    select_offer_details()
+        .filter(nft::Column::OwnerAddress.is_in(owners))
+        .filter(active_offer_condition())
+        .order_by_desc(nft_offer::Column::Price)
+        .into_model::<NftOfferDetail>()
+        .all(db)
+        .await
+}
+
+fn select_offer_details() -> Select<nft_offer::Entity> {
+    nft_offer::Entity::find()
+        .select_only()
+        .column(nft_offer::Column::Id)
+        .column(nft_offer::Column::Price)
+        .column(nft_offer::Column::Denom)
+        .column(nft_offer::Column::BuyerAddress)
+        .column(nft_offer::Column::CreatedDate)
+        .column(nft_offer::Column::StartDate)
+        .column(nft_offer::Column::EndDate)
+        .column(nft::Column::TokenAddress)
+        .column(nft::Column::TokenId)
+        .column(nft::Column::Name)
+        .column(nft::Column::Image)
+        .column(nft::Column::OwnerAddress)
+        .inner_join(nft::Entity)
+}
+
+fn active_offer_condition() -> Condition {
+    Condition::all()
+        .add(
+            Expr::col((nft_offer::Entity, nft_offer::Column::StartDate))
+                .lte(Expr::current_timestamp()),
+        )
+        .add(
+            Expr::col((nft_offer::Entity, nft_offer::Column::EndDate))
+                .gt(Expr::current_timestamp()),
+        )
+}
@@ -0,0 +1,91 @@
+use crate::{
+    database::{
+        entity::{nft, nft_rarity, nft_trait},
+        model::NftTraitValue,
+    },
+    service::RarityScore,
+};
+use sea_orm::{
+    sea_query::Query, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+
+static INSERT_CHUNK_SIZE: usize = 1000;
+
+// every nft of the collection is returned, the ones without traits score on their missing traits
+pub async fn find_collection_traits(
+    db: &DatabaseConnection,
+    token_address: &str,
+) -> Result<(Vec<i32>, Vec<NftTraitValue>), DbErr> {
+    let nft_ids = nft::Entity::find()
+        .select_only()
+        .column(nft::Column::Id)
+        .filter(nft::Column::TokenAddress.eq(token_address))
+        .into_tuple::<i32>()
+        .all(db)
+        .await?;
+
+    let traits = nft_trait::Entity::find()
+        .select_only()
+        .column(nft_trait::Column::NftId)
+        .column(nft_trait::Column::Attribute)
+        .column(nft_trait::Column::Value)
+        .inner_join(nft::Entity)
+        .filter(nft::Column::TokenAddress.eq(token_address))
+        .order_by_asc(nft_trait::Column::Id)
+        .into_model::<NftTraitValue>()
+        .all(db)
+        .await?;
+
+    Ok((nft_ids, traits))
+}
+
+// ranks only make sense together, so the scores of a collection are swapped all at once
+pub async fn replace_collection_rarities(
+    db: &DatabaseConnection,
+    token_address: &str,
+    scores: Vec<RarityScore>,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    nft_rarity::Entity::delete_many()
+        .filter(
+            nft_rarity::Column::NftId.in_subquery(
+                Query::select()
+                    .column(nft::Column::Id)
+                    .from(nft::Entity)
+                    .and_where(nft::Column::TokenAddress.eq(token_address))
+                    .to_owned(),
+            ),
+        )
+        .exec(&txn)
+        .await?;
+
+    for chunk in scores.chunks(INSERT_CHUNK_SIZE) {
+        let rarities = chunk.iter().map(|score| nft_rarity::ActiveModel {
+            nft_id: Set(score.nft_id),
+            method: Set(score.method.to_owned()),
+            score: Set(score.score),
+            rank: Set(score.rank),
+        });
+
+        nft_rarity::Entity::insert_many(rarities)
+            .on_empty_do_nothing()
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn find_by_nft_id(
+    db: &DatabaseConnection,
+    nft_id: i32,
+) -> Result<Vec<nft_rarity::Model>, DbErr> {
+    nft_rarity::Entity::find()
+        .filter(nft_rarity::Column::NftId.eq(nft_id))
+        .all(db)
+        .await
+}
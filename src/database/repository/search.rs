@@ -0,0 +1,101 @@
+use crate::database::model::SearchResult;
+use sea_orm::{DatabaseConnection, DbBackend, DbErr, FromQueryResult, Statement};
+
+// shorter texts are not matched against addresses, every address shares the first chars
+static MIN_ADDRESS_PREFIX: usize = 8;
+
+// every branch is capped before the union so the trigram indexes do the heavy lifting,
+// see search_index.sql
+static SEARCH_QUERY: &str = r#"
+SELECT * FROM (
+    SELECT
+        'collection' "kind",
+        "c"."address",
+        NULL::varchar "token_id",
+        "c"."name",
+        "c"."image",
+        greatest(
+            word_similarity($1, lower("c"."name")),
+            word_similarity($1, lower("c"."symbol")),
+            CASE WHEN lower("c"."address") LIKE $2 THEN 1 ELSE 0 END
+        )::real "score",
+        coalesce("s"."volume", 0) "volume"
+    FROM "collection" "c"
+    LEFT JOIN "collection_stats" "s" ON "s"."collection_address" = "c"."address"
+    WHERE $1 <% lower("c"."name")
+    OR $1 <% lower("c"."symbol")
+    OR lower("c"."address") LIKE $2
+    ORDER BY "score" DESC, "volume" DESC
+    LIMIT $4
+) "collections"
+UNION ALL
+SELECT * FROM (
+    SELECT
+        'nft' "kind",
+        "n"."token_address" "address",
+        "n"."token_id",
+        "n"."name",
+        "n"."image",
+        greatest(
+            coalesce(word_similarity($1, lower("n"."name")), 0),
+            CASE WHEN "n"."token_id" = $3 THEN 1 ELSE 0 END,
+            coalesce((
+                SELECT max(word_similarity($1, lower("t"."value")))
+                FROM "nft_trait" "t"
+                WHERE "t"."nft_id" = "n"."id"
+                AND $1 <% lower("t"."value")
+            ), 0)
+        )::real "score",
+        coalesce("s"."volume", 0) "volume"
+    FROM "nft" "n"
+    LEFT JOIN "collection_stats" "s" ON "s"."collection_address" = "n"."token_address"
+    WHERE $1 <% lower("n"."name")
+    OR "n"."token_id" = $3
+    OR EXISTS (
+        SELECT 1 FROM "nft_trait" "t"
+        WHERE "t"."nft_id" = "n"."id"
+        AND $1 <% lower("t"."value")
+    )
+    ORDER BY "score" DESC, "volume" DESC
+    LIMIT $4
+) "nfts"
+ORDER BY "score" DESC, "volume" DESC
+LIMIT $4
+"#;
+
+// collections match by name, symbol or address prefix, nfts by name, exact token id or trait value
+pub async fn search(
+    db: &DatabaseConnection,
+    text: &str,
+    limit: u8,
+) -> Result<Vec<SearchResult>, DbErr> {
+    let lowercase = text.to_lowercase();
+
+    // a null prefix matches nothing, short texts like "sei" would rank every collection first
+    let address_prefix = is_address_like(&lowercase).then(|| {
+        format!(
+            "{}%",
+            lowercase
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        )
+    });
+
+    SearchResult::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        SEARCH_QUERY,
+        [
+            lowercase.into(),
+            address_prefix.into(),
+            text.into(),
+            (limit as i64).into(),
+        ],
+    ))
+    .all(db)
+    .await
+}
+
+fn is_address_like(text: &str) -> bool {
+    text.len() >= MIN_ADDRESS_PREFIX && (text.starts_with("sei1") || text.starts_with("0x"))
+}
@@ -1,18 +1,25 @@
 use crate::database::entity::{sea_orm_active_enums::StreamContext, stream_tx};
 use sea_orm::{
     prelude::DateTimeWithTimeZone, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
-    QueryFilter, Set,
+    PaginatorTrait, QueryFilter, Set,
 };
 
-#[allow(dead_code)]
-pub async fn find_stream_tx_by_tx_hash(
+// failed events are not counted, a replay gets to retry them
+pub async fn is_handled(
     db: &DatabaseConnection,
     tx_hash: &str,
-) -> Result<Option<stream_tx::Model>, DbErr> {
-    stream_tx::Entity::find()
+    context: StreamContext,
+    event: serde_json::Value,
+) -> Result<bool, DbErr> {
+    let handled = stream_tx::Entity::find()
         .filter(stream_tx::Column::TxHash.eq(tx_hash))
-        .one(db)
-        .await
+        .filter(stream_tx::Column::Context.eq(context))
+        .filter(stream_tx::Column::Event.eq(event))
+        .filter(stream_tx::Column::IsFailure.eq(false))
+        .count(db)
+        .await?;
+
+    Ok(handled > 0)
 }
 
 pub async fn create_stream_tx(
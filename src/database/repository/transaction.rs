@@ -1,9 +1,14 @@
 use sea_orm::{
     prelude::{DateTimeUtc, Decimal},
-    DatabaseTransaction, DbErr, EntityTrait, Set,
+    sea_query::Expr,
+    ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, QueryTrait, Set,
 };
 
-use crate::database::entity::{sea_orm_active_enums::Marketplace, transaction};
+use crate::database::{
+    entity::{sea_orm_active_enums::Marketplace, transaction},
+    model::{PriceCandle, RoyaltyPoint},
+};
 
 pub async fn create(
     tx: &DatabaseTransaction,
@@ -17,6 +22,8 @@ pub async fn create(
         seller_address: Set(params.seller_address),
         txn_hash: Set(params.tx_hash),
         volume: Set(params.volume),
+        royalty: Set(params.royalty),
+        royalty_address: Set(params.royalty_address),
         ..Default::default()
     };
 
@@ -25,6 +32,92 @@ pub async fn create(
     Ok(())
 }
 
+// only buckets with at least one sale are returned
+pub async fn find_price_candles(
+    db: &DatabaseConnection,
+    params: FindPriceCandlesParams,
+) -> Result<Vec<PriceCandle>, DbErr> {
+    transaction::Entity::find()
+        .select_only()
+        .column_as(
+            Expr::cust(format!(
+                r#"floor(extract(epoch FROM "date") / {})::bigint"#,
+                params.interval_seconds
+            )),
+            "bucket",
+        )
+        .column_as(
+            Expr::cust(r#"(array_agg("volume" ORDER BY "date", "id"))[1]"#),
+            "open",
+        )
+        .column_as(transaction::Column::Volume.max(), "high")
+        .column_as(transaction::Column::Volume.min(), "low")
+        .column_as(
+            Expr::cust(r#"(array_agg("volume" ORDER BY "date" DESC, "id" DESC))[1]"#),
+            "close",
+        )
+        .column_as(transaction::Column::Volume.sum(), "volume")
+        .column_as(transaction::Column::Id.count(), "sales")
+        .filter(transaction::Column::CollectionAddress.eq(&params.address))
+        .filter(transaction::Column::Date.gte(params.from))
+        .filter(transaction::Column::Date.lt(params.to))
+        .apply_if(params.market, |query, market| {
+            query.filter(transaction::Column::Market.eq(market))
+        })
+        .group_by(Expr::cust("bucket"))
+        .order_by_asc(Expr::cust("bucket"))
+        .into_model::<PriceCandle>()
+        .all(db)
+        .await
+}
+
+pub async fn find_last_price_before(
+    db: &DatabaseConnection,
+    address: &str,
+    date: DateTimeUtc,
+    market: Option<Marketplace>,
+) -> Result<Option<Decimal>, DbErr> {
+    let transaction = transaction::Entity::find()
+        .filter(transaction::Column::CollectionAddress.eq(address))
+        .filter(transaction::Column::Date.lt(date))
+        .apply_if(market, |query, market| {
+            query.filter(transaction::Column::Market.eq(market))
+        })
+        .order_by_desc(transaction::Column::Date)
+        .order_by_desc(transaction::Column::Id)
+        .one(db)
+        .await?;
+
+    Ok(transaction.map(|transaction| transaction.volume))
+}
+
+// only buckets with at least one sale are returned
+pub async fn find_royalty_points(
+    db: &DatabaseConnection,
+    params: FindRoyaltyPointsParams,
+) -> Result<Vec<RoyaltyPoint>, DbErr> {
+    transaction::Entity::find()
+        .select_only()
+        .column_as(
+            Expr::cust(format!(
+                r#"floor(extract(epoch FROM "date") / {})::bigint"#,
+                params.interval_seconds
+            )),
+            "bucket",
+        )
+        .column_as(transaction::Column::Royalty.sum(), "royalty")
+        .column_as(transaction::Column::Volume.sum(), "volume")
+        .column_as(transaction::Column::Id.count(), "sales")
+        .filter(transaction::Column::CollectionAddress.eq(&params.address))
+        .filter(transaction::Column::Date.gte(params.from))
+        .filter(transaction::Column::Date.lt(params.to))
+        .group_by(Expr::cust("bucket"))
+        .order_by_asc(Expr::cust("bucket"))
+        .into_model::<RoyaltyPoint>()
+        .all(db)
+        .await
+}
+
 pub struct CreateTransactionParams {
     pub tx_hash: String,
     pub volume: Decimal,
@@ -33,4 +126,21 @@ pub struct CreateTransactionParams {
     pub seller_address: String,
     pub created_date: DateTimeUtc,
     pub marketplace: Marketplace,
+    pub royalty: Decimal,
+    pub royalty_address: Option<String>,
+}
+
+pub struct FindPriceCandlesParams {
+    pub address: String,
+    pub interval_seconds: i64,
+    pub from: DateTimeUtc,
+    pub to: DateTimeUtc,
+    pub market: Option<Marketplace>,
+}
+
+pub struct FindRoyaltyPointsParams {
+    pub address: String,
+    pub interval_seconds: i64,
+    pub from: DateTimeUtc,
+    pub to: DateTimeUtc,
 }
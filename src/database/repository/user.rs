@@ -0,0 +1,28 @@
+use crate::database::entity::user;
+use sea_orm::{sea_query::OnConflict, DatabaseConnection, DbErr, EntityTrait, Set};
+
+// is_new_user keeps its default on the first login, later logins leave the row untouched
+pub async fn create_if_not_exist(
+    db: &DatabaseConnection,
+    address: &str,
+) -> Result<user::Model, DbErr> {
+    let user = user::ActiveModel {
+        address: Set(address.to_owned()),
+        ..Default::default()
+    };
+
+    user::Entity::insert(user)
+        .on_conflict(
+            OnConflict::column(user::Column::Address)
+                .do_nothing()
+                .to_owned(),
+        )
+        .do_nothing()
+        .exec(db)
+        .await?;
+
+    user::Entity::find_by_id(address)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(format!("user {} not found", address)))
+}
@@ -1,13 +1,16 @@
 use sea_orm::{
     prelude::DateTimeUtc,
-    sea_query::{Alias, Expr, Func, NullOrdering, PostgresQueryBuilder, Query, WindowStatement},
+    sea_query::{
+        Alias, Expr, Func, NullOrdering, PostgresQueryBuilder, Query, SimpleExpr, WindowStatement,
+    },
     ColumnTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, EntityTrait,
-    FromQueryResult, Order, Set, Statement,
+    FromQueryResult, Order, QueryFilter, QueryOrder, QuerySelect, Set, Statement,
 };
 
 use crate::database::{
-    entity::{sea_orm_active_enums::LoyaltyPointKind, user_loyalty_point},
-    model::{Count, LeaderboardParticipant},
+    entity::{sea_orm_active_enums::LoyaltyPointKind, user_loyalty_point, wallet_link},
+    model::{Count, LeaderboardParticipant, PointTotal},
+    repository::wallet_link as WalletLinkRepository,
 };
 
 pub async fn create(tx: &DatabaseTransaction, params: CreateUserPointParams) -> Result<(), DbErr> {
@@ -49,6 +52,8 @@ pub async fn find_leaderboad_by_date(
     let total = count_leaderboard_participants_by_date(db, from, to).await?;
 
     if let Some(wallet_address) = wallet_address {
+        let wallet_address = WalletLinkRepository::to_sei_address(db, wallet_address).await?;
+
         user_on_leaderboard =
             find_leaderboard_participants_by_date(db, from, to, None, Some(wallet_address))
                 .await?
@@ -59,6 +64,50 @@ pub async fn find_leaderboad_by_date(
     Ok((participants, total, user_on_leaderboard))
 }
 
+pub async fn find_point_totals(
+    db: &DatabaseConnection,
+    wallet_addresses: &[String],
+) -> Result<Vec<PointTotal>, DbErr> {
+    user_loyalty_point::Entity::find()
+        .select_only()
+        .column(user_loyalty_point::Column::Kind)
+        .column_as(user_loyalty_point::Column::Point.sum(), "point")
+        .filter(user_loyalty_point::Column::WalletAddress.is_in(wallet_addresses))
+        .group_by(user_loyalty_point::Column::Kind)
+        .into_model::<PointTotal>()
+        .all(db)
+        .await
+}
+
+pub async fn find_points(
+    db: &DatabaseConnection,
+    wallet_addresses: &[String],
+    page: u64,
+    limit: u8,
+) -> Result<(Vec<user_loyalty_point::Model>, i64), DbErr> {
+    let skip = (page - 1) * limit as u64;
+
+    let points = user_loyalty_point::Entity::find()
+        .filter(user_loyalty_point::Column::WalletAddress.is_in(wallet_addresses))
+        .order_by_desc(user_loyalty_point::Column::Date)
+        .order_by_desc(user_loyalty_point::Column::Id)
+        .limit(limit as u64)
+        .offset(skip)
+        .all(db)
+        .await?;
+
+    let total = user_loyalty_point::Entity::find()
+        .select_only()
+        .column_as(user_loyalty_point::Column::Id.count(), "count")
+        .filter(user_loyalty_point::Column::WalletAddress.is_in(wallet_addresses))
+        .into_model::<Count>()
+        .one(db)
+        .await?
+        .unwrap_or_default();
+
+    Ok((points, total.count))
+}
+
 async fn find_leaderboard_participants_by_date(
     db: &DatabaseConnection,
     from: DateTimeUtc,
@@ -70,7 +119,7 @@ async fn find_leaderboard_participants_by_date(
 
     query.expr(Expr::cust("*")).from_subquery(
         Query::select()
-            .column(user_loyalty_point::Column::WalletAddress)
+            .expr_as(linked_wallet_address(), Alias::new("wallet_address"))
             .expr_as(user_loyalty_point::Column::Point.sum(), Alias::new("point"))
             .expr_window(
                 Expr::cust("rank()"),
@@ -83,11 +132,12 @@ async fn find_leaderboard_participants_by_date(
                     .take(),
             )
             .from(user_loyalty_point::Entity)
+            .left_join(wallet_link::Entity, wallet_link_condition())
             .and_where(user_loyalty_point::Column::Date.gte(from))
             .and_where(user_loyalty_point::Column::Date.lt(to))
             .and_where(user_loyalty_point::Column::Point.gt(0))
             .and_where(user_loyalty_point::Column::Kind.ne(LoyaltyPointKind::Xp))
-            .group_by_col(user_loyalty_point::Column::WalletAddress)
+            .add_group_by([linked_wallet_address()])
             .order_by_expr_with_nulls(
                 user_loyalty_point::Column::Point.sum(),
                 Order::Desc,
@@ -128,12 +178,13 @@ async fn count_leaderboard_participants_by_date(
         .from_subquery(
             Query::select()
                 .distinct()
-                .column(user_loyalty_point::Column::WalletAddress)
+                .expr(linked_wallet_address())
                 .and_where(user_loyalty_point::Column::Date.gte(from))
                 .and_where(user_loyalty_point::Column::Date.lt(to))
                 .and_where(user_loyalty_point::Column::Point.gt(0))
                 .and_where(user_loyalty_point::Column::Kind.ne(LoyaltyPointKind::Xp))
                 .from(user_loyalty_point::Entity)
+                .left_join(wallet_link::Entity, wallet_link_condition())
                 .to_owned(),
             Alias::new("tmp"),
         )
@@ -151,6 +202,26 @@ async fn count_leaderboard_participants_by_date(
     Ok(total.count)
 }
 
+// points earned from an evm address are merged into its linked sei address
+fn linked_wallet_address() -> SimpleExpr {
+    Func::coalesce([
+        Expr::col((wallet_link::Entity, wallet_link::Column::SeiAddress)).into(),
+        Expr::col((
+            user_loyalty_point::Entity,
+            user_loyalty_point::Column::WalletAddress,
+        ))
+        .into(),
+    ])
+    .into()
+}
+
+fn wallet_link_condition() -> SimpleExpr {
+    Expr::col((wallet_link::Entity, wallet_link::Column::EvmAddress)).equals((
+        user_loyalty_point::Entity,
+        user_loyalty_point::Column::WalletAddress,
+    ))
+}
+
 pub struct CreateUserPointParams {
     pub date: DateTimeUtc,
     pub kind: LoyaltyPointKind,
@@ -0,0 +1,68 @@
+use crate::database::entity::wallet_link;
+use sea_orm::{
+    sea_query::OnConflict, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, Set,
+};
+
+pub async fn find_by_evm_address(
+    db: &DatabaseConnection,
+    evm_address: &str,
+) -> Result<Option<wallet_link::Model>, DbErr> {
+    wallet_link::Entity::find()
+        .filter(wallet_link::Column::EvmAddress.eq(evm_address.to_lowercase()))
+        .one(db)
+        .await
+}
+
+pub async fn create(
+    db: &DatabaseConnection,
+    sei_address: String,
+    evm_address: String,
+) -> Result<(), DbErr> {
+    let link = wallet_link::ActiveModel {
+        sei_address: Set(sei_address),
+        evm_address: Set(evm_address.to_lowercase()),
+    };
+
+    wallet_link::Entity::insert(link)
+        .on_conflict(
+            OnConflict::column(wallet_link::Column::SeiAddress)
+                .update_column(wallet_link::Column::EvmAddress)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+// both formats are accepted from outside, but we always store the sei one when it is known
+pub async fn to_sei_address(db: &DatabaseConnection, address: String) -> Result<String, DbErr> {
+    if !address.starts_with("0x") {
+        return Ok(address);
+    }
+
+    let link = find_by_evm_address(db, &address).await?;
+
+    Ok(link.map(|link| link.sei_address).unwrap_or(address))
+}
+
+// a wallet may act through its sei and its evm address, data of both belongs to the same person
+pub async fn find_linked_addresses(
+    db: &DatabaseConnection,
+    address: &str,
+) -> Result<Vec<String>, DbErr> {
+    let link = wallet_link::Entity::find()
+        .filter(
+            Condition::any()
+                .add(wallet_link::Column::SeiAddress.eq(address))
+                .add(wallet_link::Column::EvmAddress.eq(address.to_lowercase())),
+        )
+        .one(db)
+        .await?;
+
+    Ok(match link {
+        Some(link) => vec![link.sei_address, link.evm_address],
+        None => vec![address.to_owned()],
+    })
+}
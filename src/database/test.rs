@@ -0,0 +1,141 @@
+use super::entity::{sea_orm_active_enums::*, *};
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, EntityTrait, Schema,
+};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// prisma owns the migrations, the entities miss its defaults and composite uniques
+static PRISMA_EXTRAS: &str = r#"
+ALTER TABLE "collection" ALTER COLUMN "supply" SET DEFAULT 1;
+ALTER TABLE "collection" ALTER COLUMN "rarity_outdated" SET DEFAULT true;
+ALTER TABLE "collection_stats" ALTER COLUMN "listed" SET DEFAULT 0;
+ALTER TABLE "collection_stats" ALTER COLUMN "floor_price" SET DEFAULT 0;
+ALTER TABLE "collection_stats" ALTER COLUMN "ceiling_price" SET DEFAULT 0;
+ALTER TABLE "collection_stats" ALTER COLUMN "sales" SET DEFAULT 0;
+ALTER TABLE "collection_stats" ALTER COLUMN "volume" SET DEFAULT 0;
+ALTER TABLE "collection_stats" ALTER COLUMN "volume_of_1h" SET DEFAULT 0;
+ALTER TABLE "collection_stats" ALTER COLUMN "volume_of_24h" SET DEFAULT 0;
+ALTER TABLE "collection_stats" ALTER COLUMN "volume_of_7d" SET DEFAULT 0;
+ALTER TABLE "collection_stats" ALTER COLUMN "volume_of_30d" SET DEFAULT 0;
+ALTER TABLE "collection_stats" ALTER COLUMN "updated_at" SET DEFAULT CURRENT_TIMESTAMP;
+ALTER TABLE "collection_offer" ALTER COLUMN "current_quantity" SET DEFAULT 0;
+ALTER TABLE "collection_snapshot" ALTER COLUMN "date" SET DEFAULT CURRENT_TIMESTAMP;
+ALTER TABLE "collection_snapshot" ALTER COLUMN "listed" SET DEFAULT 0;
+ALTER TABLE "collection_snapshot" ALTER COLUMN "sales_of_24h" SET DEFAULT 0;
+ALTER TABLE "collection_snapshot" ALTER COLUMN "holders" SET DEFAULT 0;
+ALTER TABLE "failure_stream_tx" ALTER COLUMN "date" SET DEFAULT CURRENT_TIMESTAMP;
+ALTER TABLE "failure_stream_tx" ALTER COLUMN "context" SET DEFAULT 'mrkt';
+ALTER TABLE "launchpad" ALTER COLUMN "total_minted" SET DEFAULT 0;
+ALTER TABLE "launchpad" ALTER COLUMN "mint_revenue" SET DEFAULT 0;
+ALTER TABLE "launchpad" ALTER COLUMN "denom" SET DEFAULT 'usei';
+ALTER TABLE "launchpad_phase" ALTER COLUMN "minted" SET DEFAULT 0;
+ALTER TABLE "listing_nft" ALTER COLUMN "market" SET DEFAULT 'mrkt';
+ALTER TABLE "metadata_refresh" ALTER COLUMN "date" SET DEFAULT CURRENT_TIMESTAMP;
+ALTER TABLE "nft_activity" ALTER COLUMN "market" SET DEFAULT 'mrkt';
+ALTER TABLE "stream_tx" ALTER COLUMN "date" SET DEFAULT CURRENT_TIMESTAMP;
+ALTER TABLE "stream_tx" ALTER COLUMN "is_failure" SET DEFAULT false;
+ALTER TABLE "stream_tx" ALTER COLUMN "context" SET DEFAULT 'mrkt';
+ALTER TABLE "transaction" ALTER COLUMN "market" SET DEFAULT 'mrkt';
+ALTER TABLE "transaction" ALTER COLUMN "royalty" SET DEFAULT 0;
+ALTER TABLE "user" ALTER COLUMN "is_new_user" SET DEFAULT true;
+CREATE UNIQUE INDEX ON "collection_offer" ("collection_address", "buyer_address", "price");
+CREATE UNIQUE INDEX ON "failure_stream_tx" ("tx_hash");
+CREATE UNIQUE INDEX ON "launchpad_phase" ("collection_address", "name");
+CREATE UNIQUE INDEX ON "launchpad_mint" ("collection_address", "token_id");
+CREATE UNIQUE INDEX ON "listing_nft" ("nft_id");
+CREATE UNIQUE INDEX ON "nft" ("token_address", "token_id");
+CREATE UNIQUE INDEX ON "nft_offer" ("nft_id", "buyer_address", "price");
+"#;
+
+static SCHEMA_COUNT: AtomicU32 = AtomicU32::new(0);
+
+// every call gets its own postgres schema so tests can run in parallel,
+// returns None when TEST_DATABASE_URL is not set and the test should be skipped
+pub async fn connect() -> Option<DatabaseConnection> {
+    let db_url = std::env::var("TEST_DATABASE_URL").ok()?;
+
+    let schema = format!(
+        "test_{}_{}",
+        std::process::id(),
+        SCHEMA_COUNT.fetch_add(1, Ordering::SeqCst)
+    );
+
+    let admin = Database::connect(ConnectOptions::new(db_url.to_owned()))
+        .await
+        .unwrap();
+    admin
+        .execute_unprepared(&format!(
+            r#"DROP SCHEMA IF EXISTS "{0}" CASCADE; CREATE SCHEMA "{0}";"#,
+            schema
+        ))
+        .await
+        .unwrap();
+    admin.close().await.unwrap();
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.sqlx_logging(false).set_schema_search_path(schema);
+
+    let db = Database::connect(opt).await.unwrap();
+
+    create_schema(&db).await;
+
+    Some(db)
+}
+
+async fn create_schema(db: &DatabaseConnection) {
+    let schema = Schema::new(DbBackend::Postgres);
+
+    let enums = vec![
+        schema.create_enum_from_active_enum::<LoyaltyPointKind>(),
+        schema.create_enum_from_active_enum::<Marketplace>(),
+        schema.create_enum_from_active_enum::<NftActivityKind>(),
+        schema.create_enum_from_active_enum::<RarityMethod>(),
+        schema.create_enum_from_active_enum::<SaleType>(),
+        schema.create_enum_from_active_enum::<StreamContext>(),
+    ];
+
+    for statement in enums {
+        db.execute(db.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+
+    // referenced tables first, collection_view is created from its sql below
+    create_table(db, &schema, collection::Entity).await;
+    create_table(db, &schema, collection_stats::Entity).await;
+    create_table(db, &schema, collection_offer::Entity).await;
+    create_table(db, &schema, collection_snapshot::Entity).await;
+    create_table(db, &schema, config::Entity).await;
+    create_table(db, &schema, failure_stream_tx::Entity).await;
+    create_table(db, &schema, launchpad::Entity).await;
+    create_table(db, &schema, launchpad_phase::Entity).await;
+    create_table(db, &schema, nft::Entity).await;
+    create_table(db, &schema, launchpad_mint::Entity).await;
+    create_table(db, &schema, listing_nft::Entity).await;
+    create_table(db, &schema, nft_bidding::Entity).await;
+    create_table(db, &schema, nft_activity::Entity).await;
+    create_table(db, &schema, nft_offer::Entity).await;
+    create_table(db, &schema, nft_rarity::Entity).await;
+    create_table(db, &schema, nft_trait::Entity).await;
+    create_table(db, &schema, metadata_refresh::Entity).await;
+    create_table(db, &schema, missing_stream_block::Entity).await;
+    create_table(db, &schema, stream_tx::Entity).await;
+    create_table(db, &schema, transaction::Entity).await;
+    create_table(db, &schema, user::Entity).await;
+    create_table(db, &schema, user_loyalty_point::Entity).await;
+    create_table(db, &schema, wallet_link::Entity).await;
+
+    db.execute_unprepared(PRISMA_EXTRAS).await.unwrap();
+    // the view names the public schema, the test schema is reached through the search path
+    let collection_view = include_str!("../../collection_view.sql").replace(r#""public"."#, "");
+
+    db.execute_unprepared(&collection_view).await.unwrap();
+}
+
+async fn create_table<E: EntityTrait>(db: &DatabaseConnection, schema: &Schema, entity: E) {
+    let statement = schema.create_table_from_entity(entity);
+
+    db.execute(db.get_database_backend().build(&statement))
+        .await
+        .unwrap();
+}
@@ -1,4 +1,5 @@
 use axum::{
+    http::header,
     response::{IntoResponse, Response},
     Json,
 };
@@ -27,6 +28,12 @@ pub enum AppError {
     #[error("{0}")]
     Unauthorized(String),
 
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("Too many requests, retry after {0} seconds")]
+    TooManyRequests(u64),
+
     // internal
     #[error("Unexpected eror: {0}")]
     Unexpected(String),
@@ -43,12 +50,24 @@ pub enum AppError {
     #[error("Redis error: {0}")]
     Redis(#[from] deadpool_redis::PoolError),
 
+    #[error("Redis command error: {0}")]
+    RedisCommand(#[from] deadpool_redis::redis::RedisError),
+
     #[error("HttpRequest error: {0}")]
     HttpRequest(#[from] reqwest::Error),
 
     #[error("Cosmos error: {0}")]
     Cosmos(#[from] crate::service::CosmosClientError),
 
+    #[error("Metadata error: {0}")]
+    Metadata(#[from] crate::service::MetadataError),
+
+    #[error("Evm error: {0}")]
+    Evm(#[from] crate::service::EvmClientError),
+
+    #[error("Jwt error: {0}")]
+    Jwt(#[from] jsonwebtoken::errors::Error),
+
     #[error("Sqlx error: {0}")]
     Sqlx(#[from] sea_orm::SqlxError),
 
@@ -58,6 +77,15 @@ pub enum AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        if let AppError::TooManyRequests(retry_after) = self {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                to_json(StatusCode::TOO_MANY_REQUESTS, self.to_string()),
+            )
+                .into_response();
+        }
+
         match self {
             AppError::Validation(error) => (
                 StatusCode::BAD_REQUEST,
@@ -83,6 +111,10 @@ impl IntoResponse for AppError {
                 StatusCode::UNAUTHORIZED,
                 to_json(StatusCode::UNAUTHORIZED, reason),
             ),
+            AppError::NotFound(reason) => (
+                StatusCode::NOT_FOUND,
+                to_json(StatusCode::NOT_FOUND, reason),
+            ),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 to_json(StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
@@ -1,3 +1,4 @@
+mod cache;
 mod database;
 mod error;
 mod schedule;
@@ -5,9 +6,14 @@ mod server;
 mod service;
 mod r#static;
 mod stream;
+mod supervisor;
 mod watcher;
 
-pub use schedule::background;
+#[cfg(feature = "fake")]
+pub use service::fake;
+
+pub use schedule::{background, rebuild_collection_stats};
 pub use server::server;
-pub use stream::{cw721_stream, pallet_stream};
+pub use stream::{contract_stream, cw721_stream, erc721_stream, launchpad_stream, pallet_stream};
+pub use supervisor::supervisor;
 pub use watcher::watcher;
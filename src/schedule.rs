@@ -2,17 +2,55 @@ mod background;
 mod cronjob_expression;
 
 use self::{background::Background, cronjob_expression::CronExpression};
-use crate::error::AppError;
-use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use crate::{
+    database::{
+        self,
+        repository::{
+            collection as CollectionRepository,
+            collection_snapshot::{self as CollectionSnapshotRepository, SnapshotResolution},
+            collection_stats as CollectionStatsRepository, nft_rarity as NftRarityRepository,
+        },
+    },
+    error::AppError,
+    server::rate_limit::claim_cooldown,
+    service::compute_rarity_scores,
+    stream::{
+        create_stream_client,
+        metadata::{
+            collection_refresh_key, refresh_collection, COLLECTION_REFRESH_COOLDOWN_SECONDS,
+        },
+    },
+};
+use chrono::{Duration, Utc};
+use sea_orm::DatabaseConnection;
+
+static DEFAULT_SNAPSHOT_CRON: &str = "0 */5 * * * *";
+static DEFAULT_SNAPSHOT_RAW_RETENTION_DAYS: i64 = 7;
+static DEFAULT_SNAPSHOT_HOURLY_RETENTION_DAYS: i64 = 90;
+static DEFAULT_UNREVEALED_RECHECK_HOURS: i64 = 6;
+// every nft of a collection is fetched, so only a few collections are rechecked per run
+static UNREVEALED_BATCH_SIZE: u64 = 3;
 
 pub async fn background() {
     dotenv::dotenv().ok();
-    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
+    run_background(database::connect().await).await
+}
+
+// one shot repair of collection_stats, e.g. after a stream bug or a manual fix of the data
+pub async fn rebuild_collection_stats() {
+    dotenv::dotenv().ok();
+
+    let db = database::connect().await;
 
-    let mut opt = ConnectOptions::new(db_url);
-    opt.sqlx_logging(false);
+    match CollectionStatsRepository::rebuild(&db).await {
+        Ok(collections) => println!("🦀 rebuilt stats of {} collections", collections),
+        Err(error) => eprintln!("error when rebuild collection stats \n>>{}", error),
+    }
+}
 
-    let db = Database::connect(opt).await.unwrap();
+pub async fn run_background(db: DatabaseConnection) {
+    // six fields with seconds first, e.g. SNAPSHOT_CRON="0 */5 * * * *"
+    let snapshot_cron = std::env::var("SNAPSHOT_CRON").unwrap_or(DEFAULT_SNAPSHOT_CRON.to_owned());
 
     Background::new()
         .set_context(db)
@@ -22,9 +60,27 @@ pub async fn background() {
         // .add_job(CronExpression::Every5Seconds, &|db| {
         //     Box::pin(async move { run_per_5_seconds(db).await })
         // })
-        .add_job("run??", CronExpression::Every10Seconds, &|db| {
-            Box::pin(async move { run_per_10_seconds(db).await })
+        .add_job("collection_stats", CronExpression::Every30Seconds, &|db| {
+            Box::pin(async move { refresh_collection_stats(db).await })
         })
+        .add_job("nft_rarity", CronExpression::Every30Seconds, &|db| {
+            Box::pin(async move { refresh_rarities(db).await })
+        })
+        .add_job(
+            "collection_snapshot",
+            CronExpression::Custom(snapshot_cron),
+            &|db| Box::pin(async move { snapshot_collections(db).await }),
+        )
+        .add_job(
+            "collection_snapshot_downsample",
+            CronExpression::Every30Minutes,
+            &|db| Box::pin(async move { downsample_collection_snapshots(db).await }),
+        )
+        .add_job(
+            "unrevealed_metadata",
+            CronExpression::Every30Minutes,
+            &|db| Box::pin(async move { refresh_unrevealed_metadata(db).await }),
+        )
         .start()
         .await;
 }
@@ -39,7 +95,146 @@ pub async fn background() {
 //     Ok(())
 // }
 
-async fn run_per_10_seconds(_db: DatabaseConnection) -> Result<(), AppError> {
-    println!("run every 10 secords");
+// sales and listings update the stats as they happen, this only moves the volume windows
+// forward and drops listings that expired meanwhile
+async fn refresh_collection_stats(db: DatabaseConnection) -> Result<(), AppError> {
+    CollectionStatsRepository::refresh_rolling_stats(&db).await?;
+
+    Ok(())
+}
+
+// mints and metadata refreshes only flag the collection, a mint out would recompute it on every nft otherwise
+async fn refresh_rarities(db: DatabaseConnection) -> Result<(), AppError> {
+    for address in CollectionRepository::find_rarity_outdated(&db).await? {
+        // cleared first, so traits changing during the computation flag it again for the next run
+        CollectionRepository::set_rarity_outdated(&db, &address, false).await?;
+
+        if let Err(error) = refresh_collection_rarity(&db, &address).await {
+            CollectionRepository::set_rarity_outdated(&db, &address, true).await?;
+
+            return Err(error);
+        }
+    }
+
+    Ok(())
+}
+
+async fn refresh_collection_rarity(db: &DatabaseConnection, address: &str) -> Result<(), AppError> {
+    let (nft_ids, traits) = NftRarityRepository::find_collection_traits(db, address).await?;
+
+    let supply = nft_ids.len();
+    let scores = compute_rarity_scores(&nft_ids, traits);
+
+    NftRarityRepository::replace_collection_rarities(db, address, scores).await?;
+
+    println!("🦀 ranked rarity of {} nfts of {}", supply, address);
+
+    Ok(())
+}
+
+async fn snapshot_collections(db: DatabaseConnection) -> Result<(), AppError> {
+    let snapshots = CollectionSnapshotRepository::create_snapshots(&db, Utc::now()).await?;
+
+    println!("🦀 snapshot {} collections", snapshots);
+
+    Ok(())
+}
+
+// every snapshot is kept for SNAPSHOT_RAW_RETENTION_DAYS, then one per hour until
+// SNAPSHOT_HOURLY_RETENTION_DAYS, then one per day forever
+async fn downsample_collection_snapshots(db: DatabaseConnection) -> Result<(), AppError> {
+    let raw_retention = retention_days(
+        "SNAPSHOT_RAW_RETENTION_DAYS",
+        DEFAULT_SNAPSHOT_RAW_RETENTION_DAYS,
+    );
+    let hourly_retention = retention_days(
+        "SNAPSHOT_HOURLY_RETENTION_DAYS",
+        DEFAULT_SNAPSHOT_HOURLY_RETENTION_DAYS,
+    );
+
+    let now = Utc::now();
+
+    let hourly = CollectionSnapshotRepository::downsample(
+        &db,
+        SnapshotResolution::Hour,
+        now - Duration::days(raw_retention),
+    )
+    .await?;
+
+    let daily = CollectionSnapshotRepository::downsample(
+        &db,
+        SnapshotResolution::Day,
+        now - Duration::days(hourly_retention),
+    )
+    .await?;
+
+    if hourly + daily > 0 {
+        println!(
+            "🦀 downsample collection snapshots, {} hourly and {} daily removed",
+            hourly, daily
+        );
+    }
+
     Ok(())
 }
+
+// reveals happen on chain or at the metadata host without any event we could stream,
+// so collections that still look unrevealed are refreshed every UNREVEALED_RECHECK_HOURS
+async fn refresh_unrevealed_metadata(db: DatabaseConnection) -> Result<(), AppError> {
+    let recheck_hours = std::env::var("UNREVEALED_RECHECK_HOURS")
+        .ok()
+        .and_then(|hours| hours.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_UNREVEALED_RECHECK_HOURS);
+
+    let addresses = CollectionRepository::find_unrevealed(
+        &db,
+        Utc::now() - Duration::hours(recheck_hours),
+        UNREVEALED_BATCH_SIZE,
+    )
+    .await?;
+
+    if addresses.is_empty() {
+        return Ok(());
+    }
+
+    let client = create_stream_client();
+
+    for address in addresses {
+        // a refresh requested through the api is already running or has just run
+        if let Some(redis_pool) = &client.cache {
+            if claim_cooldown(
+                redis_pool,
+                &collection_refresh_key(&address),
+                COLLECTION_REFRESH_COOLDOWN_SECONDS,
+            )
+            .await
+            .is_err()
+            {
+                continue;
+            }
+        }
+
+        match refresh_collection(&db, &client, &address).await {
+            Ok(refresh) => println!(
+                "🦀 rechecked unrevealed {}, {} of {} nfts changed, {} failed",
+                address, refresh.changed_nfts, refresh.refreshed_nfts, refresh.failed_nfts
+            ),
+            Err(error) => {
+                eprintln!("error when recheck unrevealed {} \n>>{}", address, error);
+
+                CollectionRepository::set_metadata_refreshed(&db, &address)
+                    .await
+                    .unwrap_or_else(|e| eprintln!("error when set metadata refreshed \n>>{}", e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn retention_days(key: &str, default: i64) -> i64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|days| days.parse::<i64>().ok())
+        .unwrap_or(default)
+}
@@ -14,7 +14,7 @@ lazy_static::lazy_static! {
         Arc::new(RwLock::new(HashMap::new()));
 }
 
-type Worker<C> = dyn Fn(C) -> Pin<Box<dyn Future<Output = Result<(), AppError>>>>;
+type Worker<C> = dyn Fn(C) -> Pin<Box<dyn Future<Output = Result<(), AppError>> + Send>> + Sync;
 
 pub struct Background<'r, C> {
     context: Option<C>,
@@ -56,8 +56,6 @@ where
 
             let schedule = Schedule::from_str(cron_expression.to_str()).unwrap();
 
-            let mut progress = PROGRESS.write().await;
-
             let fut = async move {
                 //  under the hood
                 //  datetime that is the time the task will run in the future
@@ -70,15 +68,16 @@ where
                     if let Ok(duration) = datetime.signed_duration_since(Utc::now()).to_std() {
                         tokio::time::sleep(duration).await;
 
-                        let is_running = *progress.get(job).unwrap_or(&false);
+                        // the lock is only held around the flag, holding it through a run would block every other job
+                        let is_running = *PROGRESS.read().await.get(job).unwrap_or(&false);
 
                         if !is_running {
-                            progress.insert(&job, true);
+                            PROGRESS.write().await.insert(job, true);
 
                             worker(con.clone()).await.unwrap_or_else(|e| {
                                 eprintln!("error occur from cronjob::{} >> {}", job, e)
                             });
-                            progress.insert(&job, false);
+                            PROGRESS.write().await.insert(job, false);
                         }
                     }
                 }
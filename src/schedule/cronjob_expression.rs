@@ -83,10 +83,11 @@ pub enum CronExpression {
     MondayToFridayAt9PM,
     MondayToFridayAt10PM,
     MondayToFridayAt11PM,
+    Custom(String),
 }
 
 impl CronExpression {
-    pub fn to_str(&self) -> &'static str {
+    pub fn to_str(&self) -> &str {
         match self {
             CronExpression::EverySecond => "* * * * * *",
             CronExpression::Every5Seconds => "*/5 * * * * *",
@@ -171,6 +172,7 @@ impl CronExpression {
             CronExpression::MondayToFridayAt9PM => "0 0 21 * * 1-5",
             CronExpression::MondayToFridayAt10PM => "0 0 22 * * 1-5",
             CronExpression::MondayToFridayAt11PM => "0 0 23 * * 1-5",
+            CronExpression::Custom(expression) => expression,
         }
     }
 }
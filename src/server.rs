@@ -1,31 +1,154 @@
+mod activity_hub;
 pub mod api;
 pub mod deserialization;
 mod extract;
 mod openapi;
+pub mod rate_limit;
 mod serialization;
 
-use crate::server::extract::state::AppState;
-use axum::{routing::get, Router};
+use crate::{
+    database,
+    server::{activity_hub::ActivityHub, extract::state::AppState},
+    stream::create_stream_client,
+};
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use deadpool_redis::{Config, Runtime};
+use sea_orm::DatabaseConnection;
+use std::{net::SocketAddr, sync::Arc};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+static REDIS_URL: &str = "redis://127.0.0.1/";
+
 pub async fn server() {
     dotenv::dotenv().ok();
-    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
-    let redis_url = "redis://127.0.0.1/";
+    run_server(database::connect().await, create_redis_pool()).await
+}
 
+pub fn create_redis_pool() -> deadpool_redis::Pool {
+    Config::from_url(REDIS_URL)
+        .create_pool(Some(Runtime::Tokio1))
+        .unwrap()
+}
+
+pub async fn run_server(db: DatabaseConnection, redis_pool: deadpool_redis::Pool) {
     let address = "0.0.0.0:8098";
 
+    let activity_hub = ActivityHub::new();
+
+    tokio::spawn(activity_hub.clone().bridge(REDIS_URL));
+
+    let state = AppState {
+        db,
+        redis_pool,
+        activity_hub,
+        stream_client: std::env::var("RPC_URL")
+            .is_ok()
+            .then(|| Arc::new(create_stream_client())),
+    };
+
     let app = Router::new()
-        .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", openapi::ApiDoc::openapi()))
         .route("/api/v1/", get(|| async { "Hello, 🦀!" }))
+        .route("/api/v1/auth/nonce", get(api::auth::get_nonce))
+        .route("/api/v1/auth/login", post(api::auth::login))
+        .route("/api/v1/auth/refresh", post(api::auth::refresh_token))
+        .route("/api/v1/activities", get(api::activity::get_activities))
+        .route(
+            "/api/v1/activities/sse",
+            get(api::activity::stream_activities),
+        )
+        .route(
+            "/api/v1/activities/ws",
+            get(api::activity::subscribe_activities),
+        )
+        .route("/api/v1/search", get(api::search::get_search_results))
         .route("/api/v1/collections", get(api::collection::get_collections))
+        .route(
+            "/api/v1/collections/:address",
+            get(api::collection::get_collection),
+        )
+        .route(
+            "/api/v1/collections/:address/nfts",
+            get(api::collection::get_listed_nfts_by_collection),
+        )
+        .route(
+            "/api/v1/collections/:address/offers",
+            get(api::collection::get_collection_offers),
+        )
+        .route(
+            "/api/v1/collections/:address/activities",
+            get(api::collection::get_collection_activities),
+        )
+        .route(
+            "/api/v1/collections/:address/chart",
+            get(api::collection::get_collection_chart),
+        )
+        .route(
+            "/api/v1/collections/:address/royalties",
+            get(api::collection::get_collection_royalties),
+        )
+        .route(
+            "/api/v1/collections/:address/traits",
+            get(api::collection::get_collection_traits),
+        )
+        .route(
+            "/api/v1/collections/:address/refresh",
+            post(api::collection::refresh_collection_metadata),
+        )
+        .route("/api/v1/nfts/:collection/:token_id", get(api::nft::get_nft))
+        .route(
+            "/api/v1/nfts/:collection/:token_id/refresh",
+            post(api::nft::refresh_nft_metadata),
+        )
+        .route(
+            "/api/v1/nfts/:collection/:token_id/activities",
+            get(api::nft::get_nft_activities),
+        )
+        .route(
+            "/api/v1/wallets/:address/activities",
+            get(api::wallet::get_wallet_activities),
+        )
+        .route(
+            "/api/v1/wallets/:address/nfts",
+            get(api::wallet::get_wallet_nfts),
+        )
+        .route(
+            "/api/v1/wallets/:address/listings",
+            get(api::wallet::get_wallet_listings),
+        )
+        .route(
+            "/api/v1/wallets/:address/offers",
+            get(api::wallet::get_wallet_offers),
+        )
+        .route(
+            "/api/v1/wallets/:address/points",
+            get(api::wallet::get_wallet_points),
+        )
         .route("/api/v1/leaderboard", get(api::leaderboard::get_leaderboad))
-        .with_state(AppState::init(&db_url, redis_url).await);
+        .route(
+            "/api/v1/launchpad/:address",
+            get(api::launchpad::get_mint_progress),
+        )
+        // only the api is limited, the docs are routed after the layer
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::rate_limit,
+        ))
+        .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", openapi::ApiDoc::openapi()))
+        .with_state(state);
 
     let listener = tokio::net::TcpListener::bind(address).await.unwrap();
 
     println!("🦀 server is running on port {}", address);
 
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
@@ -0,0 +1,92 @@
+use crate::{
+    database::{model::Activity, repository::nft_activity::ActivityScope},
+    error::AppError,
+    r#static::ACTIVITY_EVENTS_CHANNEL,
+};
+use deadpool_redis::redis;
+use futures_util::StreamExt;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+// how far a client may fall behind before it is dropped, it can resume from its last event id
+static CLIENT_BUFFER: usize = 256;
+static RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+// fans the activities published by the watcher out to every client connected to this instance
+#[derive(Clone)]
+pub struct ActivityHub {
+    sender: broadcast::Sender<Activity>,
+}
+
+impl ActivityHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CLIENT_BUFFER);
+
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Activity> {
+        self.sender.subscribe()
+    }
+
+    pub async fn bridge(self, redis_url: &str) {
+        loop {
+            if let Err(error) = self.listen(redis_url).await {
+                eprintln!("activity hub is disconnected from redis \n>>{}", error);
+            }
+
+            tokio::time::sleep(RECONNECT_INTERVAL).await;
+        }
+    }
+
+    async fn listen(&self, redis_url: &str) -> Result<(), AppError> {
+        let mut pubsub = redis::Client::open(redis_url)?.get_async_pubsub().await?;
+
+        pubsub.subscribe(ACTIVITY_EVENTS_CHANNEL).await?;
+
+        println!(
+            "🦀 activity hub is listening on {}",
+            ACTIVITY_EVENTS_CHANNEL
+        );
+
+        let mut messages = pubsub.on_message();
+
+        while let Some(message) = messages.next().await {
+            let payload: String = message.get_payload()?;
+
+            match serde_json::from_str::<Activity>(&payload) {
+                // no receiver is not an error, nobody is connected right now
+                Ok(activity) => {
+                    self.sender.send(activity).ok();
+                }
+                Err(error) => eprintln!("unexpected activity event {} \n>>{}", payload, error),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ActivityHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn is_in_scope(scope: &ActivityScope, activity: &Activity) -> bool {
+    match scope {
+        ActivityScope::All => true,
+        ActivityScope::Collection(collection_address) => {
+            &activity.token_address == collection_address
+        }
+        ActivityScope::Nft(collection_address, token_id) => {
+            &activity.token_address == collection_address && &activity.token_id == token_id
+        }
+        ActivityScope::Wallet(wallet_addresses) => {
+            [&activity.seller_address, &activity.buyer_address]
+                .into_iter()
+                .flatten()
+                .any(|address| wallet_addresses.contains(address))
+        }
+    }
+}
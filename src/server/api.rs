@@ -1,2 +1,8 @@
+pub mod activity;
+pub mod auth;
 pub mod collection;
+pub mod launchpad;
 pub mod leaderboard;
+pub mod nft;
+pub mod search;
+pub mod wallet;
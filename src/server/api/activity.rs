@@ -0,0 +1,125 @@
+mod get_activities;
+mod stream_activities;
+mod subscribe_activities;
+
+pub use get_activities::*;
+pub use stream_activities::*;
+pub use subscribe_activities::*;
+
+use crate::{
+    database::{
+        model::Activity,
+        repository::{
+            nft_activity::{self as NftActivityRepository, ActivityScope},
+            wallet_link as WalletLinkRepository,
+        },
+    },
+    error::AppError,
+};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+// past this a client is far behind, it is rejected and has to resync through the activity endpoints
+static MAX_REPLAY: u64 = 500;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct ActivitySubscriptionParams {
+    pub topic: ActivityTopic,
+    /// required by the collection and nft topics
+    pub collection: Option<String>,
+    /// required by the nft topic
+    pub token_id: Option<String>,
+    /// required by the wallet topic
+    pub wallet: Option<String>,
+    /// id of the last activity received, up to 500 missed ones are replayed before the live ones
+    pub last_event_id: Option<i32>,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityTopic {
+    All,
+    Collection,
+    Nft,
+    Wallet,
+}
+
+impl ActivitySubscriptionParams {
+    fn into_scope(self) -> Result<ActivityScope, String> {
+        let required = |value: Option<String>, name: &str| {
+            value.ok_or(format!(
+                "{} is required by the {:?} topic",
+                name, self.topic
+            ))
+        };
+
+        match self.topic {
+            ActivityTopic::All => Ok(ActivityScope::All),
+            ActivityTopic::Collection => Ok(ActivityScope::Collection(required(
+                self.collection,
+                "collection",
+            )?)),
+            ActivityTopic::Nft => Ok(ActivityScope::Nft(
+                required(self.collection, "collection")?,
+                required(self.token_id, "token_id")?,
+            )),
+            ActivityTopic::Wallet => Ok(ActivityScope::Wallet(vec![required(
+                self.wallet,
+                "wallet",
+            )?])),
+        }
+    }
+}
+
+// a wallet acts through its sei and its evm address, the wallet topic follows both
+async fn find_scope(
+    db: &DatabaseConnection,
+    params: ActivitySubscriptionParams,
+) -> Result<ActivityScope, AppError> {
+    match params.into_scope().map_err(AppError::BadRequestError)? {
+        ActivityScope::Wallet(wallet_addresses) => {
+            let mut linked_addresses = Vec::new();
+
+            for wallet_address in &wallet_addresses {
+                linked_addresses
+                    .extend(WalletLinkRepository::find_linked_addresses(db, wallet_address).await?);
+            }
+
+            Ok(ActivityScope::Wallet(linked_addresses))
+        }
+        scope => Ok(scope),
+    }
+}
+
+// returns the missed activities with the id live ones have to be newer than, to not send one twice
+async fn replay(
+    db: &DatabaseConnection,
+    scope: ActivityScope,
+    last_event_id: Option<i32>,
+) -> Result<(Vec<Activity>, i32), AppError> {
+    let Some(last_event_id) = last_event_id else {
+        return Ok((Vec::new(), 0));
+    };
+
+    // one more than replayed tells whether the client is further behind than that
+    let activities =
+        NftActivityRepository::find_activities_after(db, scope, last_event_id, MAX_REPLAY + 1)
+            .await?;
+
+    if activities.len() as u64 > MAX_REPLAY {
+        return Err(AppError::BadRequestError(format!(
+            "more than {} activities were missed since {}, resync through /api/v1/activities and subscribe again",
+            MAX_REPLAY, last_event_id
+        )));
+    }
+
+    let last_seen = activities
+        .last()
+        .map(|activity| activity.id)
+        .unwrap_or(last_event_id);
+
+    Ok((activities, last_seen))
+}
@@ -0,0 +1,99 @@
+use crate::{
+    database::repository::nft_activity::{
+        self as NftActivityRepository, ActivityScope, FindActivitiesParams,
+    },
+    error::AppError,
+    server::{
+        deserialization::{ActivityKindFilter, Cursor, MarketplaceFilter},
+        extract::{state::Postgres, validate::ValidatedQuery},
+        serialization::{CursorData, SerializedResponse},
+    },
+};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use sea_orm::{prelude::Decimal, DatabaseConnection};
+use serde::Deserialize;
+use serde_json::Value;
+use utoipa::IntoParams;
+use validator::Validate;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct ActivityParams {
+    #[validate(range(min = 1, max = 100))]
+    pub limit: u8,
+    /// next_cursor of the previous page, omit it for the first page
+    #[param(value_type = Option<String>)]
+    pub cursor: Option<Cursor>,
+    pub kind: Option<ActivityKindFilter>,
+    pub market: Option<MarketplaceFilter>,
+    #[param(value_type = Option<String>)]
+    pub min_price: Option<Decimal>,
+    #[param(value_type = Option<String>)]
+    pub max_price: Option<Decimal>,
+    /// rfc3339 datetime, inclusive
+    #[param(value_type = Option<String>)]
+    pub from_date: Option<DateTime<Utc>>,
+    /// rfc3339 datetime, inclusive
+    #[param(value_type = Option<String>)]
+    pub to_date: Option<DateTime<Utc>>,
+}
+
+#[utoipa::path(
+  get,
+  params(ActivityParams),
+  path = "/api/v1/activities",
+  tag = "Activity",
+  responses(
+      (status = 200, description = "return activities of every collection")
+  )
+)]
+pub async fn get_activities(
+    ValidatedQuery(params): ValidatedQuery<ActivityParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    find_activity_feed(&db, ActivityScope::All, params).await
+}
+
+// shared by every scoped activity endpoint so their filters and cursors behave the same
+pub async fn find_activity_feed(
+    db: &DatabaseConnection,
+    scope: ActivityScope,
+    params: ActivityParams,
+) -> Result<Json<Value>, AppError> {
+    let limit = params.limit as usize;
+
+    // one extra row tells whether there is a next page without counting the whole table
+    let mut activities = NftActivityRepository::find_activities(
+        db,
+        FindActivitiesParams {
+            scope,
+            kind: params.kind.map(ActivityKindFilter::into_activity_kind),
+            market: params.market.map(MarketplaceFilter::into_marketplace),
+            min_price: params.min_price,
+            max_price: params.max_price,
+            from_date: params.from_date,
+            to_date: params.to_date,
+            cursor: params.cursor.map(|cursor| (cursor.date, cursor.id)),
+            limit: limit as u64 + 1,
+        },
+    )
+    .await?;
+
+    let has_next = activities.len() > limit;
+    activities.truncate(limit);
+
+    let next_cursor = activities.last().filter(|_| has_next).map(|activity| {
+        Cursor {
+            date: activity.date.with_timezone(&Utc),
+            id: activity.id,
+        }
+        .encode()
+    });
+
+    CursorData {
+        nodes: activities,
+        next_cursor,
+    }
+    .into_response()
+}
@@ -0,0 +1,69 @@
+use super::{find_scope, replay, ActivitySubscriptionParams};
+use crate::{
+    error::AppError,
+    server::{
+        activity_hub::{is_in_scope, ActivityHub},
+        extract::{state::Postgres, validate::ValidatedQuery},
+    },
+};
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{stream, Stream, StreamExt};
+
+#[utoipa::path(
+  get,
+  params(ActivitySubscriptionParams),
+  path = "/api/v1/activities/sse",
+  tag = "Activity",
+  responses(
+      (status = 200, description = "push activities of a topic as server sent events"),
+      (status = 400, description = "too many activities were missed, resync through the activity endpoints")
+  )
+)]
+pub async fn stream_activities(
+    headers: HeaderMap,
+    ValidatedQuery(params): ValidatedQuery<ActivitySubscriptionParams>,
+    Postgres(db): Postgres,
+    State(hub): State<ActivityHub>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, AppError> {
+    // an EventSource sends the id of its last event by itself when it reconnects
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|id| id.to_str().ok()?.parse::<i32>().ok())
+        .or(params.last_event_id);
+
+    let scope = find_scope(&db, params).await?;
+
+    // subscribed before replaying, so nothing published meanwhile is lost
+    let receiver = hub.subscribe();
+
+    let (replayed, last_seen) = replay(&db, scope.clone(), last_event_id).await?;
+
+    let live = stream::unfold(
+        (receiver, scope, last_seen),
+        |(mut receiver, scope, last_seen)| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(activity) if activity.id > last_seen && is_in_scope(&scope, &activity) => {
+                        return Some((activity, (receiver, scope, last_seen)));
+                    }
+                    Ok(_) => continue,
+                    // a lagging client is dropped instead of holding the others back, it resumes on reconnect
+                    Err(_) => return None,
+                }
+            }
+        },
+    );
+
+    let events = stream::iter(replayed).chain(live).map(|activity| {
+        Event::default()
+            .id(activity.id.to_string())
+            .event("activity")
+            .json_data(&activity)
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
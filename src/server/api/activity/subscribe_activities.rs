@@ -0,0 +1,95 @@
+use super::{find_scope, replay, ActivitySubscriptionParams};
+use crate::{
+    database::{model::Activity, repository::nft_activity::ActivityScope},
+    error::AppError,
+    server::{
+        activity_hub::{is_in_scope, ActivityHub},
+        extract::{state::Postgres, validate::ValidatedQuery},
+    },
+};
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+#[utoipa::path(
+  get,
+  params(ActivitySubscriptionParams),
+  path = "/api/v1/activities/ws",
+  tag = "Activity",
+  responses(
+      (status = 101, description = "push activities of a topic over a websocket"),
+      (status = 400, description = "too many activities were missed, resync through the activity endpoints")
+  )
+)]
+pub async fn subscribe_activities(
+    ws: WebSocketUpgrade,
+    ValidatedQuery(params): ValidatedQuery<ActivitySubscriptionParams>,
+    Postgres(db): Postgres,
+    State(hub): State<ActivityHub>,
+) -> Result<Response, AppError> {
+    let last_event_id = params.last_event_id;
+    let scope = find_scope(&db, params).await?;
+
+    // subscribed before replaying, so nothing published meanwhile is lost
+    let receiver = hub.subscribe();
+
+    let (replayed, last_seen) = replay(&db, scope.clone(), last_event_id).await?;
+
+    Ok(ws.on_upgrade(move |socket| push_activities(socket, receiver, scope, replayed, last_seen)))
+}
+
+async fn push_activities(
+    mut socket: WebSocket,
+    mut receiver: Receiver<Activity>,
+    scope: ActivityScope,
+    replayed: Vec<Activity>,
+    last_seen: i32,
+) {
+    for activity in replayed {
+        if send_activity(&mut socket, &activity).await.is_err() {
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            activity = receiver.recv() => match activity {
+                Ok(activity) => {
+                    if activity.id > last_seen
+                        && is_in_scope(&scope, &activity)
+                        && send_activity(&mut socket, &activity).await.is_err()
+                    {
+                        return;
+                    }
+                }
+                // a lagging client is dropped instead of holding the others back
+                Err(RecvError::Lagged(_)) => {
+                    let close = CloseFrame {
+                        code: close_code::AGAIN,
+                        reason: "too slow, resume from the last event id".into(),
+                    };
+
+                    socket.send(Message::Close(Some(close))).await.ok();
+                    return;
+                }
+                Err(RecvError::Closed) => return,
+            },
+
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                _ => continue,
+            },
+        }
+    }
+}
+
+async fn send_activity(socket: &mut WebSocket, activity: &Activity) -> Result<(), axum::Error> {
+    let message = serde_json::to_string(activity).map_err(axum::Error::new)?;
+
+    socket.send(Message::Text(message)).await
+}
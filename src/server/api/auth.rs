@@ -0,0 +1,27 @@
+mod get_nonce;
+mod login;
+mod refresh_token;
+
+pub use get_nonce::*;
+pub use login::*;
+pub use refresh_token::*;
+
+use serde::Serialize;
+
+static NONCE_TTL_SECONDS: u64 = 300;
+
+// keyed by the nonce itself, asking for a nonce must not cancel the challenge of someone else
+fn nonce_key(nonce: &str) -> String {
+    format!("auth_nonce:{}", nonce)
+}
+
+// the exact text the wallet signs, the nonce makes every signature single use
+fn sign_in_message(nonce: &str) -> String {
+    format!("Sign in to Oxide Sei Market\n\nNonce: {}", nonce)
+}
+
+#[derive(Serialize)]
+struct AuthTokens {
+    access_token: String,
+    refresh_token: String,
+}
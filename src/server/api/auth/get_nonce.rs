@@ -0,0 +1,50 @@
+use super::{nonce_key, sign_in_message, NONCE_TTL_SECONDS};
+use crate::{
+    error::AppError,
+    server::{
+        extract::{state::Redis, validate::ValidatedQuery},
+        serialization::SerializedResponse,
+    },
+};
+use axum::Json;
+use deadpool_redis::redis::AsyncCommands;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use utoipa::IntoParams;
+use validator::Validate;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct NonceParams {
+    /// sei address of the wallet signing in
+    #[validate(length(min = 1))]
+    pub address: String,
+}
+
+#[utoipa::path(
+  get,
+  params(NonceParams),
+  path = "/api/v1/auth/nonce",
+  tag = "Auth",
+  responses(
+      (status = 200, description = "return the message the wallet has to sign")
+  )
+)]
+pub async fn get_nonce(
+    ValidatedQuery(NonceParams { address }): ValidatedQuery<NonceParams>,
+    Redis(mut redis): Redis,
+) -> Result<Json<Value>, AppError> {
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+
+    // every issued nonce stays valid until it expires or is used, for the address it was asked for
+    redis
+        .set_ex::<_, _, ()>(nonce_key(&nonce), &address, NONCE_TTL_SECONDS)
+        .await?;
+
+    json!({
+        "nonce": nonce,
+        "message": sign_in_message(&nonce),
+        "expires_in": NONCE_TTL_SECONDS,
+    })
+    .into_response()
+}
@@ -0,0 +1,87 @@
+use super::{nonce_key, sign_in_message, AuthTokens};
+use crate::{
+    database::repository::user as UserRepository,
+    error::AppError,
+    server::{
+        extract::{
+            security::{create_access_token, create_refresh_token},
+            state::{Postgres, Redis},
+            validate::ValidatedPayload,
+        },
+        serialization::SerializedResponse,
+    },
+    service::verify_adr036_signature,
+};
+use axum::Json;
+use deadpool_redis::redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Deserialize, ToSchema, Validate)]
+pub struct LoginPayload {
+    /// sei address the nonce was issued for
+    #[validate(length(min = 1))]
+    pub address: String,
+    /// nonce of the signed sign in message
+    #[validate(length(min = 1))]
+    pub nonce: String,
+    /// base64 compressed secp256k1 public key of the wallet
+    #[validate(length(min = 1))]
+    pub pub_key: String,
+    /// base64 ADR-036 signature of the sign in message
+    #[validate(length(min = 1))]
+    pub signature: String,
+}
+
+#[derive(Serialize)]
+struct LoginResponse {
+    #[serde(flatten)]
+    tokens: AuthTokens,
+    is_new_user: bool,
+}
+
+#[utoipa::path(
+  post,
+  request_body = LoginPayload,
+  path = "/api/v1/auth/login",
+  tag = "Auth",
+  responses(
+      (status = 200, description = "return access and refresh tokens"),
+      (status = 401, description = "nonce is expired or signature is invalid")
+  )
+)]
+pub async fn login(
+    Postgres(db): Postgres,
+    Redis(mut redis): Redis,
+    ValidatedPayload(LoginPayload {
+        address,
+        nonce,
+        pub_key,
+        signature,
+    }): ValidatedPayload<LoginPayload>,
+) -> Result<Json<Value>, AppError> {
+    // consumed before verifying, a failed attempt has to ask for a new nonce
+    let nonce_address: Option<String> = redis.get_del(nonce_key(&nonce)).await?;
+
+    if nonce_address.as_ref() != Some(&address) {
+        return Err(AppError::Unauthorized(
+            "nonce is expired or never issued for this address".to_owned(),
+        ));
+    }
+
+    verify_adr036_signature(&address, &sign_in_message(&nonce), &pub_key, &signature)
+        .map_err(|e| AppError::Unauthorized(e.to_string()))?;
+
+    let user = UserRepository::create_if_not_exist(&db, &address).await?;
+
+    LoginResponse {
+        tokens: AuthTokens {
+            access_token: create_access_token(address.clone())?,
+            refresh_token: create_refresh_token(address)?,
+        },
+        is_new_user: user.is_new_user,
+    }
+    .into_response()
+}
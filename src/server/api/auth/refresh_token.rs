@@ -0,0 +1,46 @@
+use super::AuthTokens;
+use crate::{
+    error::AppError,
+    server::{
+        extract::{
+            security::{create_access_token, create_refresh_token, decode_refresh_token},
+            validate::ValidatedPayload,
+        },
+        serialization::SerializedResponse,
+    },
+};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Deserialize, ToSchema, Validate)]
+pub struct RefreshTokenPayload {
+    #[validate(length(min = 1))]
+    pub refresh_token: String,
+}
+
+#[utoipa::path(
+  post,
+  request_body = RefreshTokenPayload,
+  path = "/api/v1/auth/refresh",
+  tag = "Auth",
+  responses(
+      (status = 200, description = "return new access and refresh tokens"),
+      (status = 401, description = "refresh token is expired or invalid")
+  )
+)]
+pub async fn refresh_token(
+    ValidatedPayload(RefreshTokenPayload { refresh_token }): ValidatedPayload<RefreshTokenPayload>,
+) -> Result<Json<Value>, AppError> {
+    let claims = decode_refresh_token(&refresh_token)
+        .map_err(|_| AppError::Unauthorized("Invalid refresh token".to_owned()))?;
+
+    // the refresh token rotates too, so an active session never has to sign in again
+    AuthTokens {
+        access_token: create_access_token(claims.sub.clone())?,
+        refresh_token: create_refresh_token(claims.sub)?,
+    }
+    .into_response()
+}
@@ -1,6 +1,19 @@
 mod get_collection;
+mod get_collection_activities;
+mod get_collection_chart;
 mod get_collection_offers;
+mod get_collection_royalties;
+mod get_collection_traits;
 mod get_collections;
 mod get_listed_nfts_by_collection;
+mod refresh_collection_metadata;
 
+pub use get_collection::*;
+pub use get_collection_activities::*;
+pub use get_collection_chart::*;
+pub use get_collection_offers::*;
+pub use get_collection_royalties::*;
+pub use get_collection_traits::*;
 pub use get_collections::*;
+pub use get_listed_nfts_by_collection::*;
+pub use refresh_collection_metadata::*;
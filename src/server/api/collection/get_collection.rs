@@ -1 +1,108 @@
+use crate::{
+    cache::collection_tag,
+    database::{repository::collection as CollectionRepository, CollectionView, Marketplace},
+    error::AppError,
+    server::{
+        extract::state::{Postgres, RedisPool},
+        serialization::{cached_response, SerializedResponse},
+    },
+};
+use axum::{extract::Path, http::Uri, Json};
+use sea_orm::{prelude::Decimal, DatabaseConnection, Iterable};
+use serde::Serialize;
+use serde_json::Value;
 
+static CACHE_TTL_SECONDS: u64 = 60;
+
+#[derive(Serialize)]
+struct CollectionDetail {
+    #[serde(flatten)]
+    collection: CollectionView,
+    holders: i64,
+    listed_percentage: Decimal,
+    marketplaces: Vec<MarketplaceStats>,
+}
+
+#[derive(Serialize)]
+struct MarketplaceStats {
+    market: Marketplace,
+    floor_price: Option<Decimal>,
+    listed: i64,
+    volume: Decimal,
+    sales: i64,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "collection address or slug")
+  ),
+  path = "/api/v1/collections/{address}",
+  tag = "Collection",
+  responses(
+      (status = 200, description = "return collection with its stats"),
+      (status = 404, description = "collection not found")
+  )
+)]
+pub async fn get_collection(
+    uri: Uri,
+    Path(address): Path<String>,
+    Postgres(db): Postgres,
+    RedisPool(redis_pool): RedisPool,
+) -> Result<Json<Value>, AppError> {
+    cached_response(
+        &redis_pool,
+        &uri,
+        CACHE_TTL_SECONDS,
+        &[collection_tag(&address)],
+        || find_collection_detail(&db, &address),
+    )
+    .await
+}
+
+async fn find_collection_detail(
+    db: &DatabaseConnection,
+    address: &str,
+) -> Result<Json<Value>, AppError> {
+    let collection = CollectionRepository::find_collection_with_stats(db, address)
+        .await?
+        .ok_or(AppError::NotFound(format!(
+            "collection {} not found",
+            address
+        )))?;
+
+    let holders = CollectionRepository::count_holders(db, &collection.address).await?;
+    let floors = CollectionRepository::find_marketplace_floors(db, &collection.address).await?;
+    let volumes = CollectionRepository::find_marketplace_volumes(db, &collection.address).await?;
+
+    // every marketplace is listed, even the ones the collection is not traded on yet
+    let marketplaces = Marketplace::iter()
+        .map(|market| {
+            let floor = floors.iter().find(|floor| floor.market == market);
+            let volume = volumes.iter().find(|volume| volume.market == market);
+
+            MarketplaceStats {
+                floor_price: floor.map(|floor| floor.floor_price),
+                listed: floor.map(|floor| floor.listed).unwrap_or_default(),
+                volume: volume.map(|volume| volume.volume).unwrap_or_default(),
+                sales: volume.map(|volume| volume.sales).unwrap_or_default(),
+                market,
+            }
+        })
+        .collect();
+
+    let listed_percentage = if collection.supply > 0 {
+        (Decimal::from(collection.listed) * Decimal::ONE_HUNDRED / Decimal::from(collection.supply))
+            .round_dp(2)
+    } else {
+        Decimal::ZERO
+    };
+
+    CollectionDetail {
+        collection,
+        holders,
+        listed_percentage,
+        marketplaces,
+    }
+    .into_response()
+}
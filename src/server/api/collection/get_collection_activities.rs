@@ -0,0 +1,30 @@
+use crate::{
+    database::repository::nft_activity::ActivityScope,
+    error::AppError,
+    server::{
+        api::activity::{find_activity_feed, ActivityParams},
+        extract::{state::Postgres, validate::ValidatedQuery},
+    },
+};
+use axum::{extract::Path, Json};
+use serde_json::Value;
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "collection address"),
+    ActivityParams
+  ),
+  path = "/api/v1/collections/{address}/activities",
+  tag = "Collection",
+  responses(
+      (status = 200, description = "return activities of a collection")
+  )
+)]
+pub async fn get_collection_activities(
+    Path(address): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<ActivityParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    find_activity_feed(&db, ActivityScope::Collection(address), params).await
+}
@@ -0,0 +1,237 @@
+use crate::{
+    cache::collection_tag,
+    database::{
+        model::{FloorPoint, PriceCandle},
+        repository::{
+            collection_snapshot as CollectionSnapshotRepository,
+            transaction::{self as TransactionRepository, FindPriceCandlesParams},
+        },
+    },
+    error::AppError,
+    server::{
+        deserialization::MarketplaceFilter,
+        extract::{
+            state::{Postgres, RedisPool},
+            validate::ValidatedQuery,
+        },
+        serialization::{cached_response, SerializedResponse},
+    },
+};
+use axum::{extract::Path, http::Uri, Json};
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{prelude::Decimal, DatabaseConnection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+static CACHE_TTL_SECONDS: u64 = 60;
+static DEFAULT_BUCKETS: i64 = 100;
+static MAX_BUCKETS: i64 = 1000;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct ChartParams {
+    pub interval: ChartInterval,
+    /// rfc3339 datetime, defaults to 100 intervals before `to`
+    #[param(value_type = Option<String>)]
+    pub from: Option<DateTime<Utc>>,
+    /// rfc3339 datetime, defaults to now
+    #[param(value_type = Option<String>)]
+    pub to: Option<DateTime<Utc>>,
+    /// filters the sales, the floor is always the one across marketplaces
+    pub market: Option<MarketplaceFilter>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy)]
+pub enum ChartInterval {
+    #[serde(rename = "1h")]
+    _1h,
+
+    #[serde(rename = "4h")]
+    _4h,
+
+    #[serde(rename = "1d")]
+    _1d,
+
+    #[serde(rename = "1w")]
+    _1w,
+}
+
+impl ChartInterval {
+    pub(super) fn seconds(self) -> i64 {
+        match self {
+            Self::_1h => 3_600,
+            Self::_4h => 4 * 3_600,
+            Self::_1d => 24 * 3_600,
+            Self::_1w => 7 * 24 * 3_600,
+        }
+    }
+}
+
+// a bucket without sales is flat at the previous close, prices are missing until the first sale
+#[derive(Serialize)]
+struct ChartPoint {
+    time: DateTime<Utc>,
+    open: Option<Decimal>,
+    high: Option<Decimal>,
+    low: Option<Decimal>,
+    close: Option<Decimal>,
+    volume: Decimal,
+    sales: i64,
+    floor: Option<Decimal>,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "collection address"),
+    ChartParams
+  ),
+  path = "/api/v1/collections/{address}/chart",
+  tag = "Collection",
+  responses(
+      (status = 200, description = "return sale candles, volume and floor of a collection by interval")
+  )
+)]
+pub async fn get_collection_chart(
+    uri: Uri,
+    Path(address): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<ChartParams>,
+    Postgres(db): Postgres,
+    RedisPool(redis_pool): RedisPool,
+) -> Result<Json<Value>, AppError> {
+    cached_response(
+        &redis_pool,
+        &uri,
+        CACHE_TTL_SECONDS,
+        &[collection_tag(&address)],
+        || find_collection_chart(&db, address.clone(), params),
+    )
+    .await
+}
+
+async fn find_collection_chart(
+    db: &DatabaseConnection,
+    address: String,
+    params: ChartParams,
+) -> Result<Json<Value>, AppError> {
+    let interval_seconds = params.interval.seconds();
+
+    let to = params.to.unwrap_or(Utc::now());
+    let from = params
+        .from
+        .unwrap_or(to - Duration::seconds(interval_seconds * DEFAULT_BUCKETS));
+
+    if from >= to {
+        return Err(AppError::BadRequestError(
+            "from must be before to".to_owned(),
+        ));
+    }
+
+    // buckets are aligned on the unix epoch so every client sees the same candles
+    let first_bucket = from.timestamp().div_euclid(interval_seconds);
+    let last_bucket = (to.timestamp() - 1).div_euclid(interval_seconds);
+
+    if last_bucket - first_bucket + 1 > MAX_BUCKETS {
+        return Err(AppError::BadRequestError(format!(
+            "range is too large, at most {} intervals are returned",
+            MAX_BUCKETS
+        )));
+    }
+
+    let bucket_time =
+        |bucket: i64| DateTime::from_timestamp(bucket * interval_seconds, 0).unwrap_or_default();
+
+    let range_from = bucket_time(first_bucket);
+    let range_to = bucket_time(last_bucket + 1);
+    let market = params.market.map(MarketplaceFilter::into_marketplace);
+
+    let candles = TransactionRepository::find_price_candles(
+        db,
+        FindPriceCandlesParams {
+            address: address.clone(),
+            interval_seconds,
+            from: range_from,
+            to: range_to,
+            market: market.clone(),
+        },
+    )
+    .await?;
+
+    let floors = CollectionSnapshotRepository::find_floors(
+        db,
+        &address,
+        interval_seconds,
+        range_from,
+        range_to,
+    )
+    .await?;
+
+    let last_price =
+        TransactionRepository::find_last_price_before(db, &address, range_from, market).await?;
+    let last_floor =
+        CollectionSnapshotRepository::find_last_floor_before(db, &address, range_from).await?;
+
+    let points = fill_buckets(
+        first_bucket..=last_bucket,
+        candles,
+        floors,
+        last_price,
+        last_floor,
+        bucket_time,
+    );
+
+    serde_json::json!({
+        "interval": params.interval,
+        "points": points,
+    })
+    .into_response()
+}
+
+fn fill_buckets(
+    buckets: std::ops::RangeInclusive<i64>,
+    candles: Vec<PriceCandle>,
+    floors: Vec<FloorPoint>,
+    mut last_price: Option<Decimal>,
+    mut last_floor: Option<Decimal>,
+    bucket_time: impl Fn(i64) -> DateTime<Utc>,
+) -> Vec<ChartPoint> {
+    let mut candles = candles.into_iter().peekable();
+    let mut floors = floors.into_iter().peekable();
+
+    buckets
+        .map(|bucket| {
+            if let Some(floor) = floors.next_if(|floor| floor.bucket == bucket) {
+                last_floor = Some(floor.floor);
+            }
+
+            let point = match candles.next_if(|candle| candle.bucket == bucket) {
+                Some(candle) => ChartPoint {
+                    time: bucket_time(bucket),
+                    open: Some(candle.open),
+                    high: Some(candle.high),
+                    low: Some(candle.low),
+                    close: Some(candle.close),
+                    volume: candle.volume,
+                    sales: candle.sales,
+                    floor: last_floor,
+                },
+                None => ChartPoint {
+                    time: bucket_time(bucket),
+                    open: last_price,
+                    high: last_price,
+                    low: last_price,
+                    close: last_price,
+                    volume: Decimal::ZERO,
+                    sales: 0,
+                    floor: last_floor,
+                },
+            };
+
+            last_price = point.close;
+
+            point
+        })
+        .collect()
+}
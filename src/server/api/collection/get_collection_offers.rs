@@ -1 +1,52 @@
+use crate::{
+    database::repository::collection_offer as CollectionOfferRepository,
+    error::AppError,
+    server::{
+        extract::{state::Postgres, validate::ValidatedQuery},
+        serialization::SerializedResponse,
+    },
+};
+use axum::{extract::Path, Json};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use utoipa::IntoParams;
+use validator::Validate;
 
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct CollectionOffersParams {
+    pub buyer: Option<String>,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "collection address"),
+    CollectionOffersParams
+  ),
+  path = "/api/v1/collections/{address}/offers",
+  tag = "Collection",
+  responses(
+      (status = 200, description = "return active collection offers grouped by price")
+  )
+)]
+pub async fn get_collection_offers(
+    Path(address): Path<String>,
+    ValidatedQuery(CollectionOffersParams { buyer }): ValidatedQuery<CollectionOffersParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let levels = CollectionOfferRepository::find_price_levels(&db, &address).await?;
+
+    let offers = match buyer {
+        Some(buyer) => {
+            Some(CollectionOfferRepository::find_active_by_buyer(&db, &address, &buyer).await?)
+        }
+        None => None,
+    };
+
+    json!({
+        "levels": levels,
+        "offers": offers,
+    })
+    .into_response()
+}
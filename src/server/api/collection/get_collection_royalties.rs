@@ -0,0 +1,183 @@
+use super::ChartInterval;
+use crate::{
+    cache::collection_tag,
+    database::{
+        model::RoyaltyPoint,
+        repository::{
+            collection as CollectionRepository,
+            transaction::{self as TransactionRepository, FindRoyaltyPointsParams},
+        },
+    },
+    error::AppError,
+    server::{
+        extract::{
+            state::{Postgres, RedisPool},
+            validate::ValidatedQuery,
+        },
+        serialization::{cached_response, SerializedResponse},
+    },
+};
+use axum::{extract::Path, http::Uri, Json};
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{prelude::Decimal, DatabaseConnection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::IntoParams;
+use validator::Validate;
+
+static CACHE_TTL_SECONDS: u64 = 60;
+static DEFAULT_BUCKETS: i64 = 30;
+static MAX_BUCKETS: i64 = 1000;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct RoyaltyParams {
+    pub interval: ChartInterval,
+    /// rfc3339 datetime, defaults to 30 intervals before `to`
+    #[param(value_type = Option<String>)]
+    pub from: Option<DateTime<Utc>>,
+    /// rfc3339 datetime, defaults to now
+    #[param(value_type = Option<String>)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+struct CollectionRoyalties {
+    interval: ChartInterval,
+    royalty: Option<Decimal>,
+    royalty_address: Option<String>,
+    total_royalty: Decimal,
+    total_volume: Decimal,
+    sales: i64,
+    points: Vec<RoyaltyEarning>,
+}
+
+#[derive(Serialize)]
+struct RoyaltyEarning {
+    time: DateTime<Utc>,
+    royalty: Decimal,
+    volume: Decimal,
+    sales: i64,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "collection address"),
+    RoyaltyParams
+  ),
+  path = "/api/v1/collections/{address}/royalties",
+  tag = "Collection",
+  responses(
+      (status = 200, description = "return the royalty of a collection and what it earned from sales by interval"),
+      (status = 404, description = "collection not found")
+  )
+)]
+pub async fn get_collection_royalties(
+    uri: Uri,
+    Path(address): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<RoyaltyParams>,
+    Postgres(db): Postgres,
+    RedisPool(redis_pool): RedisPool,
+) -> Result<Json<Value>, AppError> {
+    cached_response(
+        &redis_pool,
+        &uri,
+        CACHE_TTL_SECONDS,
+        &[collection_tag(&address)],
+        || find_collection_royalties(&db, address.clone(), params),
+    )
+    .await
+}
+
+async fn find_collection_royalties(
+    db: &DatabaseConnection,
+    address: String,
+    params: RoyaltyParams,
+) -> Result<Json<Value>, AppError> {
+    let collection = CollectionRepository::find_by_address(db, &address)
+        .await?
+        .ok_or(AppError::NotFound(format!(
+            "collection {} not found",
+            address
+        )))?;
+
+    let interval_seconds = params.interval.seconds();
+
+    let to = params.to.unwrap_or(Utc::now());
+    let from = params
+        .from
+        .unwrap_or(to - Duration::seconds(interval_seconds * DEFAULT_BUCKETS));
+
+    if from >= to {
+        return Err(AppError::BadRequestError(
+            "from must be before to".to_owned(),
+        ));
+    }
+
+    // aligned on the unix epoch like the chart, so both line up on the same intervals
+    let first_bucket = from.timestamp().div_euclid(interval_seconds);
+    let last_bucket = (to.timestamp() - 1).div_euclid(interval_seconds);
+
+    if last_bucket - first_bucket + 1 > MAX_BUCKETS {
+        return Err(AppError::BadRequestError(format!(
+            "range is too large, at most {} intervals are returned",
+            MAX_BUCKETS
+        )));
+    }
+
+    let bucket_time =
+        |bucket: i64| DateTime::from_timestamp(bucket * interval_seconds, 0).unwrap_or_default();
+
+    let royalty_points = TransactionRepository::find_royalty_points(
+        db,
+        FindRoyaltyPointsParams {
+            address,
+            interval_seconds,
+            from: bucket_time(first_bucket),
+            to: bucket_time(last_bucket + 1),
+        },
+    )
+    .await?;
+
+    let points = fill_buckets(first_bucket..=last_bucket, royalty_points, bucket_time);
+
+    CollectionRoyalties {
+        interval: params.interval,
+        royalty: collection.royalty,
+        royalty_address: collection.royalty_address,
+        total_royalty: points.iter().map(|point| point.royalty).sum(),
+        total_volume: points.iter().map(|point| point.volume).sum(),
+        sales: points.iter().map(|point| point.sales).sum(),
+        points,
+    }
+    .into_response()
+}
+
+// a bucket without sales earned nothing
+fn fill_buckets(
+    buckets: std::ops::RangeInclusive<i64>,
+    royalty_points: Vec<RoyaltyPoint>,
+    bucket_time: impl Fn(i64) -> DateTime<Utc>,
+) -> Vec<RoyaltyEarning> {
+    let mut royalty_points = royalty_points.into_iter().peekable();
+
+    buckets
+        .map(
+            |bucket| match royalty_points.next_if(|point| point.bucket == bucket) {
+                Some(point) => RoyaltyEarning {
+                    time: bucket_time(bucket),
+                    royalty: point.royalty,
+                    volume: point.volume,
+                    sales: point.sales,
+                },
+                None => RoyaltyEarning {
+                    time: bucket_time(bucket),
+                    royalty: Decimal::ZERO,
+                    volume: Decimal::ZERO,
+                    sales: 0,
+                },
+            },
+        )
+        .collect()
+}
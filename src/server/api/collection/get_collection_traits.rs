@@ -0,0 +1,180 @@
+use super::get_listed_nfts_by_collection::parse_trait_filters;
+use crate::{
+    cache::collection_tag,
+    database::repository::nft::{self as NftRepository, ListedNftFilter},
+    error::AppError,
+    server::{
+        deserialization::{MarketplaceFilter, SaleTypeFilter},
+        extract::{
+            state::{Postgres, RedisPool},
+            validate::ValidatedQuery,
+        },
+        serialization::{cached_response, SerializedResponse},
+    },
+};
+use axum::{extract::Path, http::Uri, Json};
+use sea_orm::{prelude::Decimal, DatabaseConnection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use utoipa::IntoParams;
+use validator::Validate;
+
+static CACHE_TTL_SECONDS: u64 = 15;
+
+// the same filters as the listed nfts, so the facets always agree with the list next to them
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct TraitFacetsParams {
+    /// comma separated `attribute:value` pairs, e.g. `Background:Blue,Background:Red,Eyes:Laser`
+    pub traits: Option<String>,
+    #[param(value_type = Option<String>)]
+    pub min_price: Option<Decimal>,
+    #[param(value_type = Option<String>)]
+    pub max_price: Option<Decimal>,
+    pub market: Option<MarketplaceFilter>,
+    pub sale_type: Option<SaleTypeFilter>,
+}
+
+#[derive(Serialize)]
+struct TraitFacet {
+    attribute: String,
+    values: Vec<TraitValueFacet>,
+}
+
+// total and percentage are the rarity over the whole collection, count is how many nfts
+// match every other trait filter, listed and floor_price also follow the listing filters
+#[derive(Serialize)]
+struct TraitValueFacet {
+    value: String,
+    count: i64,
+    total: i64,
+    percentage: Decimal,
+    listed: i64,
+    floor_price: Option<Decimal>,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "collection address"),
+    TraitFacetsParams
+  ),
+  path = "/api/v1/collections/{address}/traits",
+  tag = "Collection",
+  responses(
+      (status = 200, description = "return every trait value of a collection with its rarity and listings")
+  )
+)]
+pub async fn get_collection_traits(
+    uri: Uri,
+    Path(address): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<TraitFacetsParams>,
+    Postgres(db): Postgres,
+    RedisPool(redis_pool): RedisPool,
+) -> Result<Json<Value>, AppError> {
+    cached_response(
+        &redis_pool,
+        &uri,
+        CACHE_TTL_SECONDS,
+        &[collection_tag(&address)],
+        || find_trait_facets(&db, address.clone(), params),
+    )
+    .await
+}
+
+async fn find_trait_facets(
+    db: &DatabaseConnection,
+    address: String,
+    TraitFacetsParams {
+        traits,
+        min_price,
+        max_price,
+        market,
+        sale_type,
+    }: TraitFacetsParams,
+) -> Result<Json<Value>, AppError> {
+    let trait_filters = parse_trait_filters(traits).map_err(AppError::BadRequestError)?;
+
+    let supply = NftRepository::count_by_token_address(db, &address).await?;
+    let totals = NftRepository::find_trait_counts(db, &address, &[]).await?;
+
+    let mut counts: HashMap<(String, String), i64> = if trait_filters.is_empty() {
+        HashMap::new()
+    } else {
+        NftRepository::find_trait_counts(db, &address, &trait_filters)
+            .await?
+            .into_iter()
+            .map(|count| ((count.attribute, count.value), count.count))
+            .collect()
+    };
+    let filtered = !trait_filters.is_empty();
+
+    let listings = NftRepository::find_trait_listings(
+        db,
+        &ListedNftFilter {
+            collection_address: address,
+            traits: trait_filters,
+            min_price,
+            max_price,
+            market: market.map(MarketplaceFilter::into_marketplace),
+            sale_type: sale_type.map(SaleTypeFilter::into_sale_type),
+        },
+    )
+    .await?;
+
+    let mut listings: HashMap<(String, String), (i64, Option<Decimal>)> = listings
+        .into_iter()
+        .map(|listing| {
+            (
+                (listing.attribute, listing.value),
+                (listing.listed, listing.floor_price),
+            )
+        })
+        .collect();
+
+    let mut facets: BTreeMap<String, Vec<TraitValueFacet>> = BTreeMap::new();
+
+    for total in totals {
+        let key = (total.attribute.to_owned(), total.value.to_owned());
+        let (listed, floor_price) = listings.remove(&key).unwrap_or_default();
+        let count = if filtered {
+            counts.remove(&key).unwrap_or_default()
+        } else {
+            total.count
+        };
+
+        let percentage = if supply > 0 {
+            (Decimal::from(total.count) * Decimal::ONE_HUNDRED / Decimal::from(supply)).round_dp(2)
+        } else {
+            Decimal::ZERO
+        };
+
+        facets
+            .entry(total.attribute)
+            .or_default()
+            .push(TraitValueFacet {
+                value: total.value,
+                count,
+                total: total.count,
+                percentage,
+                listed,
+                floor_price,
+            });
+    }
+
+    let facets: Vec<TraitFacet> = facets
+        .into_iter()
+        .map(|(attribute, mut values)| {
+            values.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.value.cmp(&b.value)));
+
+            TraitFacet { attribute, values }
+        })
+        .collect();
+
+    json!({
+        "supply": supply,
+        "attributes": facets,
+    })
+    .into_response()
+}
@@ -1,18 +1,24 @@
 use crate::{
+    cache::COLLECTIONS_TAG,
     database::repository::collection,
     error::AppError,
     server::{
         deserialization::SortDirection,
-        extract::{state::Postgres, validate::ValidatedQuery},
-        serialization::{PaginatedData, SerializedResponse},
+        extract::{
+            state::{Postgres, RedisPool},
+            validate::ValidatedQuery,
+        },
+        serialization::{cached_response, PaginatedData, SerializedResponse},
     },
 };
-use axum::Json;
+use axum::{http::Uri, Json};
 use serde::Deserialize;
 use serde_json::Value;
 use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
+static CACHE_TTL_SECONDS: u64 = 30;
+
 #[derive(Deserialize, IntoParams, Validate)]
 #[into_params(parameter_in = Query)]
 pub struct Params {
@@ -57,6 +63,7 @@ pub enum SortBy {
   )
 )]
 pub async fn get_collections(
+    uri: Uri,
     ValidatedQuery(Params {
         limit,
         page,
@@ -65,16 +72,32 @@ pub async fn get_collections(
         sort_direction,
     }): ValidatedQuery<Params>,
     Postgres(db): Postgres,
+    RedisPool(redis_pool): RedisPool,
 ) -> Result<Json<Value>, AppError> {
-    let (collections, total) =
-        collection::find_collections_with_stats(&db, search, page, limit, sort_by, sort_direction)
+    cached_response(
+        &redis_pool,
+        &uri,
+        CACHE_TTL_SECONDS,
+        &[COLLECTIONS_TAG.to_owned()],
+        || async {
+            let (collections, total) = collection::find_collections_with_stats(
+                &db,
+                search,
+                page,
+                limit,
+                sort_by,
+                sort_direction,
+            )
             .await?;
 
-    let data = PaginatedData {
-        nodes: collections,
-        page,
-        total,
-    };
+            let data = PaginatedData {
+                nodes: collections,
+                page,
+                total,
+            };
 
-    data.into_response()
+            data.into_response()
+        },
+    )
+    .await
 }
@@ -1,4 +1,145 @@
-use crate::server::extract::state::Postgres;
+use crate::{
+    cache::collection_tag,
+    database::repository::nft::{self as NftRepository, FindListedNftsParams, ListedNftFilter},
+    error::AppError,
+    server::{
+        deserialization::{MarketplaceFilter, RarityMethodFilter, SaleTypeFilter, SortDirection},
+        extract::{
+            state::{Postgres, RedisPool},
+            validate::ValidatedQuery,
+        },
+        serialization::{cached_response, PaginatedData, SerializedResponse},
+    },
+};
+use axum::{extract::Path, http::Uri, Json};
+use sea_orm::{prelude::Decimal, DatabaseConnection};
+use serde::Deserialize;
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
-#[allow(dead_code)]
-pub async fn get_listed_nfts_by_collection(Postgres(_db): Postgres) {}
+static CACHE_TTL_SECONDS: u64 = 15;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct ListedNftsParams {
+    #[validate(range(min = 1))]
+    pub limit: u8,
+    #[validate(range(min = 1))]
+    pub page: u64,
+    /// comma separated `attribute:value` pairs, e.g. `Background:Blue,Background:Red,Eyes:Laser`
+    pub traits: Option<String>,
+    #[param(value_type = Option<String>)]
+    pub min_price: Option<Decimal>,
+    #[param(value_type = Option<String>)]
+    pub max_price: Option<Decimal>,
+    pub market: Option<MarketplaceFilter>,
+    pub sale_type: Option<SaleTypeFilter>,
+    /// method of the returned rarity_rank and of the rarity sort, trait_rarity_sum by default
+    pub rarity_method: Option<RarityMethodFilter>,
+    pub sort_by: ListedNftSortBy,
+    pub sort_direction: SortDirection,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ListedNftSortBy {
+    Price,
+    ListedDate,
+    Rarity,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "collection address"),
+    ListedNftsParams
+  ),
+  path = "/api/v1/collections/{address}/nfts",
+  tag = "Collection",
+  responses(
+      (status = 200, description = "return listed nfts of a collection")
+  )
+)]
+pub async fn get_listed_nfts_by_collection(
+    uri: Uri,
+    Path(address): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<ListedNftsParams>,
+    Postgres(db): Postgres,
+    RedisPool(redis_pool): RedisPool,
+) -> Result<Json<Value>, AppError> {
+    cached_response(
+        &redis_pool,
+        &uri,
+        CACHE_TTL_SECONDS,
+        &[collection_tag(&address)],
+        || find_listed_nfts(&db, address.clone(), params),
+    )
+    .await
+}
+
+async fn find_listed_nfts(
+    db: &DatabaseConnection,
+    address: String,
+    ListedNftsParams {
+        limit,
+        page,
+        traits,
+        min_price,
+        max_price,
+        market,
+        sale_type,
+        rarity_method,
+        sort_by,
+        sort_direction,
+    }: ListedNftsParams,
+) -> Result<Json<Value>, AppError> {
+    let trait_filters = parse_trait_filters(traits).map_err(AppError::BadRequestError)?;
+
+    let (nfts, total) = NftRepository::find_listed_nfts(
+        db,
+        FindListedNftsParams {
+            filter: ListedNftFilter {
+                collection_address: address,
+                traits: trait_filters,
+                min_price,
+                max_price,
+                market: market.map(MarketplaceFilter::into_marketplace),
+                sale_type: sale_type.map(SaleTypeFilter::into_sale_type),
+            },
+            rarity_method: rarity_method.unwrap_or_default().into_rarity_method(),
+            sort_by,
+            sort_direction,
+            page,
+            limit,
+        },
+    )
+    .await?;
+
+    let data = PaginatedData {
+        nodes: nfts,
+        page,
+        total,
+    };
+
+    data.into_response()
+}
+
+pub(super) fn parse_trait_filters(traits: Option<String>) -> Result<Vec<(String, String)>, String> {
+    let mut trait_filters = Vec::new();
+
+    for pair in traits
+        .unwrap_or_default()
+        .split(',')
+        .filter(|pair| !pair.is_empty())
+    {
+        let (attribute, value) = pair.split_once(':').ok_or(format!(
+            "invalid trait filter {}, expected attribute:value",
+            pair
+        ))?;
+
+        trait_filters.push((attribute.to_owned(), value.to_owned()));
+    }
+
+    Ok(trait_filters)
+}
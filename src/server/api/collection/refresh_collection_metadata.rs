@@ -0,0 +1,85 @@
+use crate::{
+    database::repository::{collection as CollectionRepository, nft as NftRepository},
+    error::AppError,
+    server::{
+        extract::{
+            security::Guard,
+            state::{Postgres, RedisPool, Rpc},
+        },
+        rate_limit::claim_cooldown,
+        serialization::SerializedResponse,
+    },
+    stream::metadata::{
+        collection_refresh_key, refresh_collection, COLLECTION_REFRESH_COOLDOWN_SECONDS,
+    },
+};
+use axum::{extract::Path, Json};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Serialize)]
+struct CollectionRefreshQueued {
+    address: String,
+    nfts: i64,
+}
+
+#[utoipa::path(
+  post,
+  params(
+    ("address" = String, Path, description = "collection address")
+  ),
+  path = "/api/v1/collections/{address}/refresh",
+  tag = "Collection",
+  responses(
+      (status = 200, description = "queue a re-fetch of the collection metadata, supply and every nft of it"),
+      (status = 401, description = "missing or invalid token"),
+      (status = 404, description = "collection not found"),
+      (status = 429, description = "collection was refreshed recently")
+  ),
+  security(
+      ("BearerAuth" = [])
+  )
+)]
+pub async fn refresh_collection_metadata(
+    _guard: Guard,
+    Path(address): Path<String>,
+    Postgres(db): Postgres,
+    RedisPool(redis_pool): RedisPool,
+    Rpc(client): Rpc,
+) -> Result<Json<Value>, AppError> {
+    if CollectionRepository::find_by_address(&db, &address)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::NotFound(format!(
+            "collection {} not found",
+            address
+        )));
+    }
+
+    claim_cooldown(
+        &redis_pool,
+        &collection_refresh_key(&address),
+        COLLECTION_REFRESH_COOLDOWN_SECONDS,
+    )
+    .await?;
+
+    let nfts = NftRepository::count_by_token_address(&db, &address).await?;
+
+    // every token uri is fetched one after another, far longer than a request should take
+    tokio::spawn({
+        let address = address.to_owned();
+
+        async move {
+            match refresh_collection(&db, &client, &address).await {
+                Ok(refresh) => println!(
+                    "🦀 refreshed metadata of {}, {} of {} nfts changed, {} failed",
+                    address, refresh.changed_nfts, refresh.refreshed_nfts, refresh.failed_nfts
+                ),
+                Err(error) => eprintln!("error when refresh metadata of {} \n>>{}", address, error),
+            }
+        }
+    });
+
+    CollectionRefreshQueued { address, nfts }.into_response()
+}
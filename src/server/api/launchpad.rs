@@ -0,0 +1,3 @@
+mod get_mint_progress;
+
+pub use get_mint_progress::*;
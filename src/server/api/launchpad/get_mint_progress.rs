@@ -0,0 +1,146 @@
+use crate::{
+    database::repository::launchpad as LaunchpadRepository,
+    error::AppError,
+    server::{
+        extract::{state::Postgres, validate::ValidatedQuery},
+        serialization::SerializedResponse,
+    },
+};
+use axum::{extract::Path, Json};
+use chrono::Utc;
+use sea_orm::prelude::{DateTimeWithTimeZone, Decimal};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct MintProgressParams {
+    pub wallet: Option<String>,
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseStatus {
+    Upcoming,
+    Active,
+    Ended,
+}
+
+#[derive(Serialize)]
+struct MintProgress {
+    collection_address: String,
+    contract_address: String,
+    max_supply: Option<i32>,
+    total_minted: i32,
+    minted_percentage: Option<Decimal>,
+    mint_revenue: Decimal,
+    denom: String,
+    minters: i64,
+    phases: Vec<PhaseProgress>,
+}
+
+#[derive(Serialize)]
+struct PhaseProgress {
+    name: String,
+    status: PhaseStatus,
+    start_date: DateTimeWithTimeZone,
+    end_date: Option<DateTimeWithTimeZone>,
+    price: Decimal,
+    denom: String,
+    max_per_wallet: Option<i32>,
+    minted: i32,
+    wallet_minted: Option<i64>,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "collection address"),
+    MintProgressParams
+  ),
+  path = "/api/v1/launchpad/{address}",
+  tag = "Launchpad",
+  responses(
+      (status = 200, description = "return live mint progress of a collection"),
+      (status = 404, description = "collection is not minted through the launchpad")
+  )
+)]
+pub async fn get_mint_progress(
+    Path(address): Path<String>,
+    ValidatedQuery(MintProgressParams { wallet }): ValidatedQuery<MintProgressParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let launchpad = LaunchpadRepository::find_by_collection_address(&db, &address)
+        .await?
+        .ok_or(AppError::NotFound(format!(
+            "launchpad {} not found",
+            address
+        )))?;
+
+    let phases = LaunchpadRepository::find_phases(&db, &address).await?;
+    let minters = LaunchpadRepository::count_minters(&db, &address).await?;
+
+    let wallet_mints = match wallet {
+        Some(wallet) => Some(LaunchpadRepository::find_wallet_mints(&db, &address, &wallet).await?),
+        None => None,
+    };
+
+    let now = Utc::now();
+
+    let phases = phases
+        .into_iter()
+        .map(|phase| {
+            let status = if phase.start_date > now {
+                PhaseStatus::Upcoming
+            } else if phase.end_date.is_some_and(|end_date| end_date <= now) {
+                PhaseStatus::Ended
+            } else {
+                PhaseStatus::Active
+            };
+
+            let wallet_minted = wallet_mints.as_ref().map(|mints| {
+                mints
+                    .iter()
+                    .find(|mint| mint.phase == phase.name)
+                    .map(|mint| mint.minted)
+                    .unwrap_or_default()
+            });
+
+            PhaseProgress {
+                name: phase.name,
+                status,
+                start_date: phase.start_date,
+                end_date: phase.end_date,
+                price: phase.price,
+                denom: phase.denom,
+                max_per_wallet: phase.max_per_wallet,
+                minted: phase.minted,
+                wallet_minted,
+            }
+        })
+        .collect();
+
+    let minted_percentage = launchpad
+        .max_supply
+        .filter(|max_supply| *max_supply > 0)
+        .map(|max_supply| {
+            (Decimal::from(launchpad.total_minted) * Decimal::ONE_HUNDRED
+                / Decimal::from(max_supply))
+            .round_dp(2)
+        });
+
+    MintProgress {
+        collection_address: launchpad.collection_address,
+        contract_address: launchpad.contract_address,
+        max_supply: launchpad.max_supply,
+        total_minted: launchpad.total_minted,
+        minted_percentage,
+        mint_revenue: launchpad.mint_revenue,
+        denom: launchpad.denom,
+        minters,
+        phases,
+    }
+    .into_response()
+}
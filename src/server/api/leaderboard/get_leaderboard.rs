@@ -1,33 +1,158 @@
 use crate::{
-    database::repository::{self},
+    database::{
+        model::LeaderboardParticipant,
+        repository::{config as ConfigRepository, user_point as UserPointRepository},
+    },
     error::AppError,
-    server::extract::state::Postgres,
+    server::{
+        extract::{security::Guard, state::Postgres, validate::ValidatedQuery},
+        serialization::{PaginatedData, SerializedResponse},
+    },
 };
-use chrono::{Months, Utc};
+use axum::Json;
+use chrono::{DateTime, Datelike, Duration, Utc};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+static SEASON_START_DATE: &str = "season_start_date";
+static SEASON_END_DATE: &str = "season_end_date";
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct LeaderboardParams {
+    #[validate(range(min = 1))]
+    pub limit: u8,
+    #[validate(range(min = 1))]
+    pub page: u64,
+    pub period: LeaderboardPeriod,
+    /// rfc3339 datetime, required by the custom period
+    #[param(value_type = Option<String>)]
+    pub from: Option<DateTime<Utc>>,
+    /// rfc3339 datetime, the custom period ends now when it is omitted
+    #[param(value_type = Option<String>)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+// daily and weekly are calendar windows in utc, so everyone competes in the same window
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardPeriod {
+    Daily,
+    Weekly,
+    Season,
+    Custom,
+}
+
+#[derive(Serialize)]
+struct Leaderboard {
+    #[serde(flatten)]
+    participants: PaginatedData<LeaderboardParticipant>,
+    me: Option<LeaderboardParticipant>,
+}
 
 #[utoipa::path(
   get,
+  params(LeaderboardParams),
   path = "/api/v1/leaderboard",
   tag = "Leaderboard",
   responses(
-      (status = 200, description = "return leaderboard")
+      (status = 200, description = "return leaderboard, with the rank of the caller when authenticated")
+  ),
+  security(
+      (),
+      ("BearerAuth" = [])
   )
 )]
-pub async fn get_leaderboad(Postgres(db): Postgres) -> Result<(), AppError> {
-    let from = Utc::now()
-        .checked_sub_months(Months::new(10))
-        .unwrap_or_default();
+pub async fn get_leaderboad(
+    guard: Option<Guard>,
+    ValidatedQuery(LeaderboardParams {
+        limit,
+        page,
+        period,
+        from,
+        to,
+    }): ValidatedQuery<LeaderboardParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let now = Utc::now();
 
-    let to = Utc::now();
+    let (from, to) = match period {
+        LeaderboardPeriod::Daily => (start_of_day(now), now),
+        LeaderboardPeriod::Weekly => (
+            start_of_day(now) - Duration::days(now.weekday().num_days_from_monday() as i64),
+            now,
+        ),
+        LeaderboardPeriod::Season => find_season(&db, now).await?,
+        LeaderboardPeriod::Custom => (
+            from.ok_or(AppError::BadRequestError(
+                "from is required by the custom period".to_owned(),
+            ))?,
+            to.unwrap_or(now),
+        ),
+    };
 
-    repository::user_point::find_leaderboad_by_date(
+    if from >= to {
+        return Err(AppError::BadRequestError(
+            "from must be before to".to_owned(),
+        ));
+    }
+
+    let (participants, total, me) = UserPointRepository::find_leaderboad_by_date(
         &db,
         from,
         to,
-        1,
-        20,
-        Some("sei1932egdcxujcgg6r7fgpef4xj9c6glm8tyz8tpd".to_owned()),
+        page,
+        limit,
+        guard.map(|Guard(claims)| claims.address),
     )
     .await?;
-    Ok(())
+
+    Leaderboard {
+        participants: PaginatedData {
+            nodes: participants,
+            page,
+            total,
+        },
+        me,
+    }
+    .into_response()
+}
+
+// the season window is managed through the config table, an open season runs until now
+async fn find_season(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+    let start = ConfigRepository::find_by_key(db, SEASON_START_DATE)
+        .await?
+        .ok_or(AppError::BadRequestError(
+            "there is no season running".to_owned(),
+        ))?;
+
+    let end = ConfigRepository::find_by_key(db, SEASON_END_DATE).await?;
+
+    let start = parse_config_date(&start).map_err(AppError::Unexpected)?;
+
+    let end = match end {
+        Some(end) => now.min(parse_config_date(&end).map_err(AppError::Unexpected)?),
+        None => now,
+    };
+
+    Ok((start, end))
+}
+
+fn parse_config_date(date: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|e| format!("invalid season date {}: {}", date, e))
+}
+
+fn start_of_day(date: DateTime<Utc>) -> DateTime<Utc> {
+    date.date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
 }
@@ -0,0 +1,7 @@
+mod get_nft;
+mod get_nft_activities;
+mod refresh_nft_metadata;
+
+pub use get_nft::*;
+pub use get_nft_activities::*;
+pub use refresh_nft_metadata::*;
@@ -0,0 +1,131 @@
+use crate::{
+    database::{
+        model::{SalePrice, TraitRarity},
+        repository::{
+            nft as NftRepository, nft_activity as NftActivityRepository,
+            nft_offer as NftOfferRepository, nft_rarity as NftRarityRepository,
+        },
+        ListingNft, Nft, NftActivity, NftBidding, NftOffer, NftRarity,
+    },
+    error::AppError,
+    server::{
+        extract::{state::Postgres, validate::ValidatedQuery},
+        serialization::{PaginatedData, SerializedResponse},
+    },
+};
+use axum::{extract::Path, Json};
+use sea_orm::prelude::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::IntoParams;
+use validator::Validate;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct NftActivityParams {
+    /// page size of the activity history
+    #[validate(range(min = 1))]
+    pub limit: u8,
+    #[validate(range(min = 1))]
+    pub page: u64,
+}
+
+#[derive(Serialize)]
+struct NftDetail {
+    #[serde(flatten)]
+    nft: Nft,
+    traits: Vec<TraitDetail>,
+    rarity: Vec<NftRarity>,
+    listing: Option<ListingDetail>,
+    offers: Vec<NftOffer>,
+    last_sale: Option<SalePrice>,
+    sales: Vec<SalePrice>,
+    activities: PaginatedData<NftActivity>,
+}
+
+#[derive(Serialize)]
+struct TraitDetail {
+    #[serde(flatten)]
+    nft_trait: TraitRarity,
+    percentage: Decimal,
+}
+
+#[derive(Serialize)]
+struct ListingDetail {
+    #[serde(flatten)]
+    listing: ListingNft,
+    biddings: Vec<NftBidding>,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("collection" = String, Path, description = "collection address"),
+    ("token_id" = String, Path, description = "token id"),
+    NftActivityParams
+  ),
+  path = "/api/v1/nfts/{collection}/{token_id}",
+  tag = "Nft",
+  responses(
+      (status = 200, description = "return nft with its traits, listing, offers, sales and activities"),
+      (status = 404, description = "nft not found")
+  )
+)]
+pub async fn get_nft(
+    Path((collection, token_id)): Path<(String, String)>,
+    ValidatedQuery(NftActivityParams { limit, page }): ValidatedQuery<NftActivityParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let nft = NftRepository::find_by_address_and_token_id(&db, &collection, &token_id)
+        .await?
+        .ok_or(AppError::NotFound(format!(
+            "nft {} of {} not found",
+            token_id, collection
+        )))?;
+
+    let supply = NftRepository::count_by_token_address(&db, &nft.token_address).await?;
+
+    let traits = NftRepository::find_traits_with_rarity(&db, nft.id, &nft.token_address)
+        .await?
+        .into_iter()
+        .map(|nft_trait| TraitDetail {
+            percentage: if supply > 0 {
+                (Decimal::from(nft_trait.count) * Decimal::ONE_HUNDRED / Decimal::from(supply))
+                    .round_dp(2)
+            } else {
+                Decimal::ZERO
+            },
+            nft_trait,
+        })
+        .collect();
+
+    let listing = match NftRepository::find_active_listing_by_nft_id(&db, nft.id).await? {
+        Some(listing) => Some(ListingDetail {
+            biddings: NftRepository::find_biddings_by_listing_id(&db, listing.id).await?,
+            listing,
+        }),
+        None => None,
+    };
+
+    let rarity = NftRarityRepository::find_by_nft_id(&db, nft.id).await?;
+    let offers = NftOfferRepository::find_active_by_nft_id(&db, nft.id).await?;
+    let sales = NftActivityRepository::find_sales_by_nft_id(&db, nft.id).await?;
+    let (activities, total) =
+        NftActivityRepository::find_by_nft_id(&db, nft.id, page, limit).await?;
+
+    NftDetail {
+        nft,
+        traits,
+        rarity,
+        listing,
+        offers,
+        last_sale: sales.last().cloned(),
+        sales,
+        activities: PaginatedData {
+            nodes: activities,
+            page,
+            total,
+        },
+    }
+    .into_response()
+}
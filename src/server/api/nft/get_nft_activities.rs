@@ -0,0 +1,31 @@
+use crate::{
+    database::repository::nft_activity::ActivityScope,
+    error::AppError,
+    server::{
+        api::activity::{find_activity_feed, ActivityParams},
+        extract::{state::Postgres, validate::ValidatedQuery},
+    },
+};
+use axum::{extract::Path, Json};
+use serde_json::Value;
+
+#[utoipa::path(
+  get,
+  params(
+    ("collection" = String, Path, description = "collection address"),
+    ("token_id" = String, Path, description = "token id"),
+    ActivityParams
+  ),
+  path = "/api/v1/nfts/{collection}/{token_id}/activities",
+  tag = "Nft",
+  responses(
+      (status = 200, description = "return activities of an nft")
+  )
+)]
+pub async fn get_nft_activities(
+    Path((collection, token_id)): Path<(String, String)>,
+    ValidatedQuery(params): ValidatedQuery<ActivityParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    find_activity_feed(&db, ActivityScope::Nft(collection, token_id), params).await
+}
@@ -0,0 +1,64 @@
+use crate::{
+    error::AppError,
+    server::{
+        extract::{
+            security::Guard,
+            state::{Postgres, RedisPool, Rpc},
+        },
+        rate_limit::claim_cooldown,
+        serialization::SerializedResponse,
+    },
+    stream::metadata::refresh_nft,
+};
+use axum::{extract::Path, Json};
+use serde::Serialize;
+use serde_json::Value;
+
+static NFT_REFRESH_COOLDOWN_SECONDS: u64 = 60;
+
+#[derive(Serialize)]
+struct NftRefresh {
+    changed: bool,
+    changes: Option<Value>,
+}
+
+#[utoipa::path(
+  post,
+  params(
+    ("collection" = String, Path, description = "collection address"),
+    ("token_id" = String, Path, description = "token id")
+  ),
+  path = "/api/v1/nfts/{collection}/{token_id}/refresh",
+  tag = "Nft",
+  responses(
+      (status = 200, description = "re-fetch the token uri and return what changed in the metadata and traits"),
+      (status = 401, description = "missing or invalid token"),
+      (status = 404, description = "nft not found"),
+      (status = 429, description = "nft was refreshed recently")
+  ),
+  security(
+      ("BearerAuth" = [])
+  )
+)]
+pub async fn refresh_nft_metadata(
+    _guard: Guard,
+    Path((collection, token_id)): Path<(String, String)>,
+    Postgres(db): Postgres,
+    RedisPool(redis_pool): RedisPool,
+    Rpc(client): Rpc,
+) -> Result<Json<Value>, AppError> {
+    claim_cooldown(
+        &redis_pool,
+        &format!("metadata_refresh:{}:{}", collection, token_id),
+        NFT_REFRESH_COOLDOWN_SECONDS,
+    )
+    .await?;
+
+    let changes = refresh_nft(&db, &client, &collection, &token_id).await?;
+
+    NftRefresh {
+        changed: changes.is_some(),
+        changes,
+    }
+    .into_response()
+}
@@ -0,0 +1,3 @@
+mod get_search_results;
+
+pub use get_search_results::*;
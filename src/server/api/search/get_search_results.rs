@@ -0,0 +1,40 @@
+use crate::{
+    database::repository::search as SearchRepository,
+    error::AppError,
+    server::{
+        extract::{state::Postgres, validate::ValidatedQuery},
+        serialization::SerializedResponse,
+    },
+};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use utoipa::IntoParams;
+use validator::Validate;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct SearchParams {
+    #[validate(length(min = 2, max = 64))]
+    pub q: String,
+    #[validate(range(min = 1, max = 20))]
+    pub limit: u8,
+}
+
+#[utoipa::path(
+  get,
+  params(SearchParams),
+  path = "/api/v1/search",
+  tag = "Search",
+  responses(
+      (status = 200, description = "return collections and nfts matching the text, best matches first")
+  )
+)]
+pub async fn get_search_results(
+    ValidatedQuery(SearchParams { q, limit }): ValidatedQuery<SearchParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let results = SearchRepository::search(&db, q.trim(), limit).await?;
+
+    json!(results).into_response()
+}
@@ -0,0 +1,11 @@
+mod get_wallet_activities;
+mod get_wallet_listings;
+mod get_wallet_nfts;
+mod get_wallet_offers;
+mod get_wallet_points;
+
+pub use get_wallet_activities::*;
+pub use get_wallet_listings::*;
+pub use get_wallet_nfts::*;
+pub use get_wallet_offers::*;
+pub use get_wallet_points::*;
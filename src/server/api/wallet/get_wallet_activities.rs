@@ -0,0 +1,32 @@
+use crate::{
+    database::repository::{nft_activity::ActivityScope, wallet_link as WalletLinkRepository},
+    error::AppError,
+    server::{
+        api::activity::{find_activity_feed, ActivityParams},
+        extract::{state::Postgres, validate::ValidatedQuery},
+    },
+};
+use axum::{extract::Path, Json};
+use serde_json::Value;
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "wallet address, as seller or buyer"),
+    ActivityParams
+  ),
+  path = "/api/v1/wallets/{address}/activities",
+  tag = "Wallet",
+  responses(
+      (status = 200, description = "return activities of a wallet")
+  )
+)]
+pub async fn get_wallet_activities(
+    Path(address): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<ActivityParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let addresses = WalletLinkRepository::find_linked_addresses(&db, &address).await?;
+
+    find_activity_feed(&db, ActivityScope::Wallet(addresses), params).await
+}
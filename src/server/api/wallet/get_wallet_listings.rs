@@ -0,0 +1,34 @@
+use crate::{
+    database::repository::{nft as NftRepository, wallet_link as WalletLinkRepository},
+    error::AppError,
+    server::{
+        deserialization::RarityMethodFilter, extract::state::Postgres,
+        serialization::SerializedResponse,
+    },
+};
+use axum::{extract::Path, Json};
+use serde_json::Value;
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "wallet address")
+  ),
+  path = "/api/v1/wallets/{address}/listings",
+  tag = "Wallet",
+  responses(
+      (status = 200, description = "return active listings of a wallet")
+  )
+)]
+pub async fn get_wallet_listings(
+    Path(address): Path<String>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let sellers = WalletLinkRepository::find_linked_addresses(&db, &address).await?;
+
+    let rarity_method = RarityMethodFilter::default().into_rarity_method();
+
+    NftRepository::find_listings_by_sellers(&db, &sellers, rarity_method)
+        .await?
+        .into_response()
+}
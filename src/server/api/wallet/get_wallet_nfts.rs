@@ -0,0 +1,78 @@
+use crate::{
+    database::{
+        model::OwnedCollection,
+        repository::{nft as NftRepository, wallet_link as WalletLinkRepository},
+        Nft,
+    },
+    error::AppError,
+    server::{extract::state::Postgres, serialization::SerializedResponse},
+};
+use axum::{extract::Path, Json};
+use sea_orm::prelude::Decimal;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Serialize)]
+struct WalletNfts {
+    estimated_value: Decimal,
+    collections: Vec<OwnedCollectionDetail>,
+}
+
+#[derive(Serialize)]
+struct OwnedCollectionDetail {
+    #[serde(flatten)]
+    collection: OwnedCollection,
+    nfts: Vec<Nft>,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "wallet address")
+  ),
+  path = "/api/v1/wallets/{address}/nfts",
+  tag = "Wallet",
+  responses(
+      (status = 200, description = "return owned nfts grouped by collection, valued at floor")
+  )
+)]
+pub async fn get_wallet_nfts(
+    Path(address): Path<String>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let owners = WalletLinkRepository::find_linked_addresses(&db, &address).await?;
+
+    let collections = NftRepository::find_owned_collections(&db, &owners).await?;
+    let nfts = NftRepository::find_by_owners(&db, &owners).await?;
+
+    let mut nfts_by_collection: HashMap<String, Vec<Nft>> = HashMap::new();
+
+    for nft in nfts {
+        nfts_by_collection
+            .entry(nft.token_address.clone())
+            .or_default()
+            .push(nft);
+    }
+
+    let estimated_value = collections
+        .iter()
+        .map(|collection| collection.estimated_value)
+        .sum();
+
+    let collections = collections
+        .into_iter()
+        .map(|collection| OwnedCollectionDetail {
+            nfts: nfts_by_collection
+                .remove(&collection.address)
+                .unwrap_or_default(),
+            collection,
+        })
+        .collect();
+
+    WalletNfts {
+        estimated_value,
+        collections,
+    }
+    .into_response()
+}
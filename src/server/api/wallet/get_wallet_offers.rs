@@ -0,0 +1,56 @@
+use crate::{
+    database::repository::{
+        collection_offer as CollectionOfferRepository, nft_offer as NftOfferRepository,
+        wallet_link as WalletLinkRepository,
+    },
+    error::AppError,
+    server::{
+        extract::{security::Guard, state::Postgres},
+        serialization::SerializedResponse,
+    },
+};
+use axum::{extract::Path, Json};
+use serde_json::{json, Value};
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "wallet address, must be the authenticated one")
+  ),
+  path = "/api/v1/wallets/{address}/offers",
+  tag = "Wallet",
+  responses(
+      (status = 200, description = "return offers made and received by a wallet"),
+      (status = 401, description = "wallet is not the authenticated one")
+  ),
+  security(
+      ("BearerAuth" = [])
+  )
+)]
+pub async fn get_wallet_offers(
+    Guard(claims): Guard,
+    Path(address): Path<String>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let addresses = WalletLinkRepository::find_linked_addresses(&db, &address).await?;
+
+    if !addresses.contains(&claims.address) {
+        return Err(AppError::Unauthorized(
+            "offers are only visible to their wallet".to_owned(),
+        ));
+    }
+
+    let nft_offers = NftOfferRepository::find_active_by_buyers(&db, &addresses).await?;
+    let collection_offers =
+        CollectionOfferRepository::find_all_active_by_buyers(&db, &addresses).await?;
+    let received = NftOfferRepository::find_active_by_owners(&db, &addresses).await?;
+
+    json!({
+        "made": {
+            "nft_offers": nft_offers,
+            "collection_offers": collection_offers,
+        },
+        "received": received,
+    })
+    .into_response()
+}
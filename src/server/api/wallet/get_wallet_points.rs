@@ -0,0 +1,79 @@
+use crate::{
+    database::{
+        model::PointTotal,
+        repository::{user_point as UserPointRepository, wallet_link as WalletLinkRepository},
+        UserLoyaltyPoint,
+    },
+    error::AppError,
+    server::{
+        extract::{security::Guard, state::Postgres, validate::ValidatedQuery},
+        serialization::{PaginatedData, SerializedResponse},
+    },
+};
+use axum::{extract::Path, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::IntoParams;
+use validator::Validate;
+
+#[derive(Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct WalletPointsParams {
+    /// page size of the point history
+    #[validate(range(min = 1))]
+    pub limit: u8,
+    #[validate(range(min = 1))]
+    pub page: u64,
+}
+
+#[derive(Serialize)]
+struct WalletPoints {
+    total: i64,
+    totals: Vec<PointTotal>,
+    history: PaginatedData<UserLoyaltyPoint>,
+}
+
+#[utoipa::path(
+  get,
+  params(
+    ("address" = String, Path, description = "wallet address, must be the authenticated one"),
+    WalletPointsParams
+  ),
+  path = "/api/v1/wallets/{address}/points",
+  tag = "Wallet",
+  responses(
+      (status = 200, description = "return loyalty points of a wallet by kind with its history"),
+      (status = 401, description = "wallet is not the authenticated one")
+  ),
+  security(
+      ("BearerAuth" = [])
+  )
+)]
+pub async fn get_wallet_points(
+    Guard(claims): Guard,
+    Path(address): Path<String>,
+    ValidatedQuery(WalletPointsParams { limit, page }): ValidatedQuery<WalletPointsParams>,
+    Postgres(db): Postgres,
+) -> Result<Json<Value>, AppError> {
+    let addresses = WalletLinkRepository::find_linked_addresses(&db, &address).await?;
+
+    if !addresses.contains(&claims.address) {
+        return Err(AppError::Unauthorized(
+            "points are only visible to their wallet".to_owned(),
+        ));
+    }
+
+    let totals = UserPointRepository::find_point_totals(&db, &addresses).await?;
+    let (points, total) = UserPointRepository::find_points(&db, &addresses, page, limit).await?;
+
+    WalletPoints {
+        total: totals.iter().map(|total| total.point).sum(),
+        totals,
+        history: PaginatedData {
+            nodes: points,
+            page,
+            total,
+        },
+    }
+    .into_response()
+}
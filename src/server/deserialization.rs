@@ -1,5 +1,8 @@
+use crate::database::{Marketplace, NftActivityKind, RarityMethod, SaleType};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
 use sea_orm::Order;
-use serde::Deserialize;
+use serde::{de, Deserialize, Deserializer};
 use utoipa::ToSchema;
 
 #[derive(Deserialize, ToSchema, Debug)]
@@ -16,3 +19,114 @@ impl SortDirection {
         }
     }
 }
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketplaceFilter {
+    Mrkt,
+    Pallet,
+}
+
+impl MarketplaceFilter {
+    pub fn into_marketplace(self) -> Marketplace {
+        match self {
+            Self::Mrkt => Marketplace::Mrkt,
+            Self::Pallet => Marketplace::Pallet,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SaleTypeFilter {
+    Auction,
+    Fixed,
+}
+
+impl SaleTypeFilter {
+    pub fn into_sale_type(self) -> SaleType {
+        match self {
+            Self::Auction => SaleType::Auction,
+            Self::Fixed => SaleType::Fixed,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RarityMethodFilter {
+    Statistical,
+    #[default]
+    TraitRaritySum,
+    InformationContent,
+}
+
+impl RarityMethodFilter {
+    pub fn into_rarity_method(self) -> RarityMethod {
+        match self {
+            Self::Statistical => RarityMethod::Statistical,
+            Self::TraitRaritySum => RarityMethod::TraitRaritySum,
+            Self::InformationContent => RarityMethod::InformationContent,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKindFilter {
+    List,
+    Delist,
+    Sale,
+    MakeOffer,
+    CancelOffer,
+}
+
+impl ActivityKindFilter {
+    pub fn into_activity_kind(self) -> NftActivityKind {
+        match self {
+            Self::List => NftActivityKind::List,
+            Self::Delist => NftActivityKind::Delist,
+            Self::Sale => NftActivityKind::Sale,
+            Self::MakeOffer => NftActivityKind::MakeOffer,
+            Self::CancelOffer => NftActivityKind::CancelOffer,
+        }
+    }
+}
+
+// opaque to clients, it is the base64 of `date|id` of the last node they received
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    pub date: DateTime<Utc>,
+    pub id: i32,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        BASE64_URL_SAFE_NO_PAD.encode(format!("{}|{}", self.date.to_rfc3339(), self.id))
+    }
+}
+
+impl<'de> Deserialize<'de> for Cursor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        let invalid = || de::Error::custom(format!("invalid cursor {}", raw));
+
+        let decoded = BASE64_URL_SAFE_NO_PAD
+            .decode(&raw)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(invalid)?;
+
+        let (date, id) = decoded.split_once('|').ok_or_else(invalid)?;
+
+        Ok(Self {
+            date: DateTime::parse_from_rfc3339(date)
+                .map_err(|_| invalid())?
+                .with_timezone(&Utc),
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
@@ -3,8 +3,8 @@ use axum_extra::{
     headers::{authorization::Bearer, Authorization},
     TypedHeader,
 };
-use chrono::Utc;
-use jsonwebtoken::{errors::ErrorKind, DecodingKey, Validation};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{errors::ErrorKind, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 
 use crate::error::AppError;
@@ -52,7 +52,6 @@ where
 }
 
 impl Claims {
-    #[allow(dead_code)]
     pub fn new(address: String, expired: chrono::Duration) -> Self {
         Self {
             address,
@@ -62,7 +61,6 @@ impl Claims {
 }
 
 impl SubClaims {
-    #[allow(dead_code)]
     pub fn new(address: String, expired: chrono::Duration) -> Self {
         Self {
             sub: address,
@@ -70,3 +68,37 @@ impl SubClaims {
         }
     }
 }
+
+pub fn create_access_token(address: String) -> Result<String, jsonwebtoken::errors::Error> {
+    let access_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set.");
+
+    jsonwebtoken::encode(
+        &Header::default(),
+        &Claims::new(address, Duration::days(1)),
+        &EncodingKey::from_secret(access_secret.as_bytes()),
+    )
+}
+
+// signed with its own secret so a refresh token is never accepted by the Guard
+pub fn create_refresh_token(address: String) -> Result<String, jsonwebtoken::errors::Error> {
+    let refresh_secret =
+        std::env::var("JWT_REFRESH_SECRET").expect("JWT_REFRESH_SECRET must be set.");
+
+    jsonwebtoken::encode(
+        &Header::default(),
+        &SubClaims::new(address, Duration::days(30)),
+        &EncodingKey::from_secret(refresh_secret.as_bytes()),
+    )
+}
+
+pub fn decode_refresh_token(token: &str) -> Result<SubClaims, jsonwebtoken::errors::Error> {
+    let refresh_secret =
+        std::env::var("JWT_REFRESH_SECRET").expect("JWT_REFRESH_SECRET must be set.");
+
+    jsonwebtoken::decode::<SubClaims>(
+        token,
+        &DecodingKey::from_secret(refresh_secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map(|token_data| token_data.claims)
+}
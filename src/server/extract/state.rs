@@ -1,21 +1,28 @@
-use crate::error::AppError;
+use crate::{error::AppError, server::activity_hub::ActivityHub, service::StreamClient};
 use axum::{
     async_trait,
     extract::{FromRef, FromRequestParts},
     http::request::Parts,
 };
-use deadpool_redis::{Config, Runtime};
-use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
 
 pub type RedisConnection = deadpool_redis::Connection;
 
 pub struct Redis(pub RedisConnection);
+// the pool itself, for handlers that keep working when redis is unavailable
+pub struct RedisPool(pub deadpool_redis::Pool);
 pub struct Postgres(pub DatabaseConnection);
+// chain and metadata clients, rejected when the server runs without RPC_URL
+pub struct Rpc(pub Arc<StreamClient>);
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: DatabaseConnection,
     pub redis_pool: deadpool_redis::Pool,
+    pub activity_hub: ActivityHub,
+    // chain and metadata clients, only the metadata refresh needs them so RPC_URL is optional
+    pub stream_client: Option<Arc<StreamClient>>,
 }
 
 #[async_trait]
@@ -48,6 +55,36 @@ where
     }
 }
 
+#[async_trait]
+impl<S> FromRequestParts<S> for RedisPool
+where
+    S: Send + Sync,
+    deadpool_redis::Pool: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(deadpool_redis::Pool::from_ref(state)))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Rpc
+where
+    S: Send + Sync,
+    Option<Arc<StreamClient>>: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Option::<Arc<StreamClient>>::from_ref(state)
+            .map(Self)
+            .ok_or(AppError::Unexpected(
+                "rpc_url is not set, the chain can not be queried".to_owned(),
+            ))
+    }
+}
+
 impl FromRef<AppState> for DatabaseConnection {
     fn from_ref(app_state: &AppState) -> DatabaseConnection {
         app_state.db.clone()
@@ -60,21 +97,14 @@ impl FromRef<AppState> for deadpool_redis::Pool {
     }
 }
 
-impl AppState {
-    pub async fn init(db_url: &str, redis_url: &str) -> Self {
-        let mut opt = ConnectOptions::new(db_url);
-
-        opt.sqlx_logging(false);
-
-        let database_connection = Database::connect(opt).await.unwrap();
-
-        let redis_pool = Config::from_url(redis_url)
-            .create_pool(Some(Runtime::Tokio1))
-            .unwrap();
+impl FromRef<AppState> for ActivityHub {
+    fn from_ref(app_state: &AppState) -> ActivityHub {
+        app_state.activity_hub.clone()
+    }
+}
 
-        Self {
-            db: database_connection,
-            redis_pool,
-        }
+impl FromRef<AppState> for Option<Arc<StreamClient>> {
+    fn from_ref(app_state: &AppState) -> Option<Arc<StreamClient>> {
+        app_state.stream_client.clone()
     }
 }
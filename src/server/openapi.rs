@@ -1,6 +1,26 @@
-use super::api::collection::{SortBy, __path_get_collections};
-use super::api::leaderboard::__path_get_leaderboad;
-use super::deserialization::SortDirection;
+use super::api::activity::{
+    ActivityTopic, __path_get_activities, __path_stream_activities, __path_subscribe_activities,
+};
+use super::api::auth::{
+    LoginPayload, RefreshTokenPayload, __path_get_nonce, __path_login, __path_refresh_token,
+};
+use super::api::collection::{
+    ChartInterval, ListedNftSortBy, SortBy, __path_get_collection,
+    __path_get_collection_activities, __path_get_collection_chart, __path_get_collection_offers,
+    __path_get_collection_royalties, __path_get_collection_traits, __path_get_collections,
+    __path_get_listed_nfts_by_collection, __path_refresh_collection_metadata,
+};
+use super::api::launchpad::{PhaseStatus, __path_get_mint_progress};
+use super::api::leaderboard::{LeaderboardPeriod, __path_get_leaderboad};
+use super::api::nft::{__path_get_nft, __path_get_nft_activities, __path_refresh_nft_metadata};
+use super::api::search::__path_get_search_results;
+use super::api::wallet::{
+    __path_get_wallet_activities, __path_get_wallet_listings, __path_get_wallet_nfts,
+    __path_get_wallet_offers, __path_get_wallet_points,
+};
+use super::deserialization::{
+    ActivityKindFilter, MarketplaceFilter, RarityMethodFilter, SaleTypeFilter, SortDirection,
+};
 
 use utoipa::{
     openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
@@ -16,11 +36,35 @@ use utoipa::{
     contact (name = "thoanh098", url = "https://github.com/theanh098")
   ),
   paths(
+      get_search_results,
       get_collections,
+      get_collection,
+      get_listed_nfts_by_collection,
+      get_collection_offers,
+      get_collection_activities,
+      get_collection_chart,
+      get_collection_royalties,
+      get_collection_traits,
+      refresh_collection_metadata,
+      get_nft,
+      get_nft_activities,
+      refresh_nft_metadata,
+      get_wallet_activities,
+      get_wallet_nfts,
+      get_wallet_listings,
+      get_wallet_offers,
+      get_wallet_points,
+      get_activities,
+      stream_activities,
+      subscribe_activities,
+      get_nonce,
+      login,
+      refresh_token,
       get_leaderboad,
+      get_mint_progress,
     ),
     components(
-      schemas(SortDirection,SortBy),
+      schemas(SortDirection,SortBy,PhaseStatus,ListedNftSortBy,MarketplaceFilter,SaleTypeFilter,RarityMethodFilter,ActivityKindFilter,ChartInterval,LeaderboardPeriod,ActivityTopic,LoginPayload,RefreshTokenPayload),
       responses(Empty)
     ),
     modifiers(&BearerSecurity)
@@ -0,0 +1,244 @@
+use super::extract::security::Guard;
+use crate::error::AppError;
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{header::AUTHORIZATION, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use deadpool_redis::redis;
+use std::{collections::HashSet, net::SocketAddr};
+
+static API_KEY_HEADER: &str = "x-api-key";
+static LIMIT_HEADER: &str = "x-ratelimit-limit";
+static REMAINING_HEADER: &str = "x-ratelimit-remaining";
+
+// sliding window log, every request in the window is a member scored by its timestamp
+static SLIDING_WINDOW_SCRIPT: &str = r#"
+local key = KEYS[1]
+local now = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local limit = tonumber(ARGV[3])
+
+redis.call('ZREMRANGEBYSCORE', key, 0, now - window)
+
+local count = redis.call('ZCARD', key)
+
+if count < limit then
+    redis.call('ZADD', key, now, ARGV[4])
+    redis.call('PEXPIRE', key, window)
+    return {1, count + 1, 0}
+end
+
+local oldest = redis.call('ZRANGE', key, 0, 0, 'WITHSCORES')
+
+return {0, count, tonumber(oldest[2]) + window - now}
+"#;
+
+lazy_static::lazy_static! {
+    static ref CONFIG: RateLimitConfig = RateLimitConfig::from_env();
+}
+
+// RATE_LIMIT_ROUTES=/api/v1/collections=60,/api/v1/auth=10 limits requests per window by path prefix,
+// the longest matching prefix wins and everything else falls back to RATE_LIMIT_DEFAULT
+struct RateLimitConfig {
+    window_ms: u64,
+    default_limit: u64,
+    routes: Vec<(String, u64)>,
+    api_keys: HashSet<String>,
+    api_key_multiplier: u64,
+    trust_proxy: bool,
+}
+
+impl RateLimitConfig {
+    fn from_env() -> Self {
+        let number = |name: &str, default: u64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+
+        let mut routes: Vec<(String, u64)> = std::env::var("RATE_LIMIT_ROUTES")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|rule| rule.trim().split_once('='))
+            .filter_map(|(prefix, limit)| Some((prefix.to_owned(), limit.parse().ok()?)))
+            .collect();
+
+        routes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        Self {
+            window_ms: number("RATE_LIMIT_WINDOW_SECONDS", 60) * 1000,
+            default_limit: number("RATE_LIMIT_DEFAULT", 120),
+            routes,
+            api_keys: std::env::var("RATE_LIMIT_API_KEYS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+            api_key_multiplier: number("RATE_LIMIT_API_KEY_MULTIPLIER", 10),
+            trust_proxy: std::env::var("RATE_LIMIT_TRUST_PROXY").is_ok_and(|value| value == "true"),
+        }
+    }
+
+    fn find_route(&self, path: &str) -> (&str, u64) {
+        self.routes
+            .iter()
+            .find(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .map(|(prefix, limit)| (prefix.as_str(), *limit))
+            .unwrap_or(("default", self.default_limit))
+    }
+}
+
+pub async fn rate_limit(
+    State(redis_pool): State<deadpool_redis::Pool>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let (mut parts, body) = request.into_parts();
+
+    let (route, mut limit) = CONFIG.find_route(parts.uri.path());
+
+    // unknown api keys are ignored, otherwise every made up key would get a fresh quota
+    let api_key = parts
+        .headers
+        .get(API_KEY_HEADER)
+        .and_then(|key| key.to_str().ok())
+        .filter(|key| CONFIG.api_keys.contains(*key))
+        .map(ToOwned::to_owned);
+
+    let client = match api_key {
+        Some(api_key) => {
+            limit *= CONFIG.api_key_multiplier;
+            format!("key:{}", api_key)
+        }
+        None if parts.headers.contains_key(AUTHORIZATION) => {
+            match Guard::from_request_parts(&mut parts, &()).await {
+                Ok(Guard(claims)) => format!("wallet:{}", claims.address),
+                Err(_) => format!("ip:{}", client_ip(&parts.headers, &parts.extensions)),
+            }
+        }
+        None => format!("ip:{}", client_ip(&parts.headers, &parts.extensions)),
+    };
+
+    let key = format!("rate_limit:{}:{}", route, client);
+
+    // fails open, an unavailable redis must not take the whole api down
+    let (allowed, count, retry_after_ms) = match hit(&redis_pool, &key, limit).await {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("rate limit is skipped for {} \n>>{}", key, error);
+            return Ok(next.run(Request::from_parts(parts, body)).await);
+        }
+    };
+
+    if allowed == 0 {
+        return Err(AppError::TooManyRequests(
+            retry_after_ms.div_ceil(1000).max(1),
+        ));
+    }
+
+    let mut response = next.run(Request::from_parts(parts, body)).await;
+
+    let headers = response.headers_mut();
+    headers.insert(LIMIT_HEADER, HeaderValue::from(limit));
+    headers.insert(
+        REMAINING_HEADER,
+        HeaderValue::from(limit.saturating_sub(count)),
+    );
+
+    Ok(response)
+}
+
+// one expensive action per key and cooldown, fails open like the rate limit itself
+pub async fn claim_cooldown(
+    redis_pool: &deadpool_redis::Pool,
+    key: &str,
+    seconds: u64,
+) -> Result<(), AppError> {
+    let remaining = match try_claim(redis_pool, key, seconds).await {
+        Ok(remaining) => remaining,
+        Err(error) => {
+            eprintln!("cooldown is skipped for {} \n>>{}", key, error);
+            return Ok(());
+        }
+    };
+
+    match remaining {
+        Some(remaining) => Err(AppError::TooManyRequests(remaining.max(1))),
+        None => Ok(()),
+    }
+}
+
+async fn try_claim(
+    redis_pool: &deadpool_redis::Pool,
+    key: &str,
+    seconds: u64,
+) -> Result<Option<u64>, AppError> {
+    let mut redis = redis_pool.get().await?;
+
+    let claimed: Option<String> = redis::cmd("SET")
+        .arg(key)
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        .arg(seconds)
+        .query_async(&mut redis)
+        .await?;
+
+    if claimed.is_some() {
+        return Ok(None);
+    }
+
+    let ttl: i64 = redis::cmd("TTL").arg(key).query_async(&mut redis).await?;
+
+    Ok(Some(ttl.max(0) as u64))
+}
+
+async fn hit(
+    redis_pool: &deadpool_redis::Pool,
+    key: &str,
+    limit: u64,
+) -> Result<(u8, u64, u64), AppError> {
+    let mut redis = redis_pool.get().await?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let result = redis::cmd("EVAL")
+        .arg(SLIDING_WINDOW_SCRIPT)
+        .arg(1)
+        .arg(key)
+        .arg(now)
+        .arg(CONFIG.window_ms)
+        .arg(limit)
+        .arg(uuid::Uuid::new_v4().to_string())
+        .query_async(&mut redis)
+        .await?;
+
+    Ok(result)
+}
+
+// behind a proxy the socket address is the proxy itself. only the last forwarded hop is
+// appended by the trusted proxy, the ones before it are whatever the client sent
+fn client_ip(headers: &HeaderMap, extensions: &axum::http::Extensions) -> String {
+    let forwarded = CONFIG
+        .trust_proxy
+        .then(|| headers.get("x-forwarded-for"))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .map(str::to_owned);
+
+    forwarded
+        .or_else(|| {
+            extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address.ip().to_string())
+        })
+        .unwrap_or("unknown".to_owned())
+}
@@ -1,8 +1,9 @@
-use axum::Json;
+use axum::{http::Uri, Json};
 use serde::Serialize;
 use serde_json::Value;
+use std::future::Future;
 
-use crate::error::AppError;
+use crate::{cache, error::AppError};
 
 #[derive(Serialize)]
 pub struct PaginatedData<T> {
@@ -11,6 +12,12 @@ pub struct PaginatedData<T> {
     pub total: i64,
 }
 
+#[derive(Serialize)]
+pub struct CursorData<T> {
+    pub nodes: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
 pub trait SerializedResponse {
     fn remove_null_fields(&self) -> Result<Value, AppError>;
     fn into_response(self) -> Result<Json<Value>, AppError>;
@@ -31,6 +38,27 @@ where
     }
 }
 
+// keyed on the route and its normalized query, tags tell which writes make the response stale
+pub async fn cached_response<F, Fut>(
+    redis_pool: &deadpool_redis::Pool,
+    uri: &Uri,
+    ttl_seconds: u64,
+    tags: &[String],
+    compute: F,
+) -> Result<Json<Value>, AppError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Json<Value>, AppError>>,
+{
+    let key = cache::response_key(uri.path(), uri.query());
+
+    cache::get_or_compute(redis_pool, &key, ttl_seconds, tags, || async {
+        compute().await.map(|Json(value)| value)
+    })
+    .await
+    .map(Json)
+}
+
 fn recursively_remove_null_fields(value: &mut Value) {
     match value {
         Value::Object(map) => {
@@ -1,7 +1,17 @@
 mod cosmos;
+mod evm;
+#[cfg(feature = "fake")]
+pub mod fake;
 mod get_collection;
 mod get_nft;
+mod rarity;
+mod signature;
+mod stream_client;
 
 pub use cosmos::*;
+pub use evm::*;
 pub use get_collection::*;
 pub use get_nft::*;
+pub use rarity::*;
+pub use signature::*;
+pub use stream_client::*;
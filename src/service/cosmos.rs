@@ -1,3 +1,4 @@
+use async_trait::async_trait;
 use prost::{DecodeError, Message};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::json;
@@ -35,33 +36,135 @@ impl CosmosClient {
         Self(http_client)
     }
 
-    pub async fn get_cw721_contract_info(
+    pub fn as_http(&self) -> &HttpClient {
+        &self.0
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_nft_owner(
         &self,
         address: &str,
-    ) -> Result<ContractInfo, CosmosClientError> {
+        token_id: &str,
+    ) -> Result<NftOwner, CosmosClientError> {
         let msg = json!({
-            "contract_info": {}
+            "owner_of": {
+                "token_id": token_id
+            }
         });
 
         self.query_contract(address, msg).await
     }
 
-    pub async fn get_cw721_contract_supply(
+    #[allow(dead_code)]
+    pub async fn get_tx_header(
+        &self,
+        tx_hash: &str,
+    ) -> Result<header_by_hash::Response, CosmosClientError> {
+        let tx_hash = Hash::from_bytes(Algorithm::Sha256, tx_hash.as_bytes())?;
+        let header = self.as_http().header_by_hash(tx_hash).await?;
+
+        Ok(header)
+    }
+
+    async fn query_contract<T, U>(&self, address: &str, msg: T) -> Result<U, CosmosClientError>
+    where
+        T: Serialize,
+        U: DeserializeOwned,
+    {
+        let query = QueryContractRequest {
+            address: address.to_string(),
+            query_data: serde_json::to_vec(&msg)?,
+        };
+
+        let res = self
+            .as_http()
+            .abci_query(
+                Some("/cosmwasm.wasm.v1.Query/SmartContractState".to_string()),
+                query.encode_to_vec(),
+                None,
+                false,
+            )
+            .await?;
+
+        if res.code.is_err() {
+            return Err(CosmosClientError::RpcError(res.log));
+        }
+
+        let raw = QueryRawContractResponse::decode(res.value.as_slice())?;
+
+        let res = serde_json::from_slice::<U>(raw.data.as_slice())?;
+
+        Ok(res)
+    }
+}
+
+// chain queries the streams depend on, kept behind a trait so handlers can run against a fake chain
+#[async_trait]
+pub trait ChainClient: Send + Sync {
+    async fn get_cw721_contract_info(
+        &self,
+        address: &str,
+    ) -> Result<ContractInfo, CosmosClientError>;
+
+    async fn get_cw721_contract_supply(&self, address: &str) -> Result<Supply, CosmosClientError>;
+
+    async fn get_nft_info(
+        &self,
+        address: &str,
+        token_id: &str,
+    ) -> Result<NftInfo, CosmosClientError>;
+
+    async fn get_royalty_info(
+        &self,
+        address: &str,
+        token_id: &str,
+        sale_price: u128,
+    ) -> Result<RoyaltyInfo, CosmosClientError>;
+
+    async fn get_pallet_listing(
+        &self,
+        token_address: &str,
+        token_id: &str,
+    ) -> Result<PalletListing, CosmosClientError>;
+
+    async fn get_tx(&self, tx_hash: &str) -> Result<tx::Response, CosmosClientError>;
+
+    async fn search_tx(
+        &self,
+        query: Query,
+        page: u32,
+        per_page: u8,
+    ) -> Result<tx_search::Response, CosmosClientError>;
+
+    async fn get_pointer(
+        &self,
+        pointer_type: PointerType,
+        pointee: &str,
+    ) -> Result<Option<String>, CosmosClientError>;
+}
+
+#[async_trait]
+impl ChainClient for CosmosClient {
+    async fn get_cw721_contract_info(
         &self,
         address: &str,
-    ) -> Result<Supply, CosmosClientError> {
+    ) -> Result<ContractInfo, CosmosClientError> {
         let msg = json!({
-            "num_tokens": {}
+            "contract_info": {}
         });
 
         self.query_contract(address, msg).await
     }
 
-    pub fn as_http(&self) -> &HttpClient {
-        &self.0
+    async fn get_cw721_contract_supply(&self, address: &str) -> Result<Supply, CosmosClientError> {
+        let msg = json!({
+            "num_tokens": {}
+        });
+
+        self.query_contract(address, msg).await
     }
 
-    pub async fn get_nft_info(
+    async fn get_nft_info(
         &self,
         address: &str,
         token_id: &str,
@@ -75,22 +178,28 @@ impl CosmosClient {
         self.query_contract(address, msg).await
     }
 
-    #[allow(dead_code)]
-    pub async fn get_nft_owner(
+    // cw2981, contracts without the extension reject the query
+    async fn get_royalty_info(
         &self,
         address: &str,
         token_id: &str,
-    ) -> Result<NftOwner, CosmosClientError> {
+        sale_price: u128,
+    ) -> Result<RoyaltyInfo, CosmosClientError> {
         let msg = json!({
-            "owner_of": {
-                "token_id": token_id
+            "extension": {
+                "msg": {
+                    "royalty_info": {
+                        "token_id": token_id,
+                        "sale_price": sale_price.to_string()
+                    }
+                }
             }
         });
 
         self.query_contract(address, msg).await
     }
 
-    pub async fn get_pallet_listing(
+    async fn get_pallet_listing(
         &self,
         token_address: &str,
         token_id: &str,
@@ -105,26 +214,14 @@ impl CosmosClient {
         self.query_contract(PALLET_CONTRACT_ADDRESS, msg).await
     }
 
-    pub async fn get_tx(&self, tx_hash: &str) -> Result<tx::Response, CosmosClientError> {
+    async fn get_tx(&self, tx_hash: &str) -> Result<tx::Response, CosmosClientError> {
         let tx_hash = Hash::from_bytes(Algorithm::Sha256, tx_hash.as_bytes())?;
         let tx = self.as_http().tx(tx_hash.to_owned(), false).await?;
 
         Ok(tx)
     }
 
-    #[allow(dead_code)]
-    pub async fn get_tx_header(
-        &self,
-        tx_hash: &str,
-    ) -> Result<header_by_hash::Response, CosmosClientError> {
-        let tx_hash = Hash::from_bytes(Algorithm::Sha256, tx_hash.as_bytes())?;
-        let header = self.as_http().header_by_hash(tx_hash).await?;
-
-        Ok(header)
-    }
-
-    #[allow(dead_code)]
-    pub async fn search_tx(
+    async fn search_tx(
         &self,
         query: Query,
         page: u32,
@@ -138,20 +235,21 @@ impl CosmosClient {
         Ok(res)
     }
 
-    async fn query_contract<T, U>(&self, address: &str, msg: T) -> Result<U, CosmosClientError>
-    where
-        T: Serialize,
-        U: DeserializeOwned,
-    {
-        let query = QueryContractRequest {
-            address: address.to_string(),
-            query_data: serde_json::to_vec(&msg)?,
+    // sei keeps a registry of pointer contracts, the pointer of an erc721 is a cw721 contract and vice versa
+    async fn get_pointer(
+        &self,
+        pointer_type: PointerType,
+        pointee: &str,
+    ) -> Result<Option<String>, CosmosClientError> {
+        let query = QueryPointerRequest {
+            pointer_type: pointer_type as i32,
+            pointee: pointee.to_string(),
         };
 
         let res = self
             .as_http()
             .abci_query(
-                Some("/cosmwasm.wasm.v1.Query/SmartContractState".to_string()),
+                Some("/seiprotocol.seichain.evm.Query/Pointer".to_string()),
                 query.encode_to_vec(),
                 None,
                 false,
@@ -159,66 +257,99 @@ impl CosmosClient {
             .await?;
 
         if res.code.is_err() {
-            return Err(CosmosClientError::RpcError(res.log));
+            return Ok(None);
         }
 
-        let raw = QueryRawContractResponse::decode(res.value.as_slice())?;
-
-        let res = serde_json::from_slice::<U>(raw.data.as_slice())?;
+        let pointer = QueryPointerResponse::decode(res.value.as_slice())?;
 
-        Ok(res)
+        Ok(pointer.exists.then_some(pointer.pointer))
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct ContractInfo {
     pub name: String,
     pub symbol: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Supply {
     pub count: u32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct NftInfo {
     pub token_uri: String,
     pub extension: Option<Extension>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Extension {
     pub royalty_percentage: Option<f32>,
 }
 
-#[derive(Deserialize, Debug)]
+// royalty_amount is an Uint128 of the sale price denom, address is empty when there is no royalty
+#[derive(Deserialize, Debug, Clone)]
+pub struct RoyaltyInfo {
+    pub address: String,
+    pub royalty_amount: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
 pub struct NftOwner {
     pub owner: String,
 }
 
-#[derive(Deserialize, Debug)]
-
+#[derive(Deserialize, Debug, Clone)]
 pub struct PalletListing {
     pub owner: String,
     pub auction: Option<PalletAuction>,
 }
 
-#[derive(Deserialize, Debug)]
-
+#[derive(Deserialize, Debug, Clone)]
 pub struct PalletAuction {
     pub created_at: u32,
     pub expiration_time: u32,
     pub prices: [Price; 1],
 }
 
-#[derive(Deserialize, Debug)]
-
+#[derive(Deserialize, Debug, Clone)]
 pub struct Price {
     pub amount: String,
     pub denom: String,
 }
 
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointerType {
+    Erc20 = 0,
+    Erc721 = 1,
+    Native = 2,
+    Cw20 = 3,
+    Cw721 = 4,
+}
+
+#[derive(prost::Message)]
+struct QueryPointerRequest {
+    #[prost(int32, tag = "1")]
+    pointer_type: i32,
+
+    #[prost(string, tag = "2")]
+    pointee: prost::alloc::string::String,
+}
+
+#[derive(prost::Message)]
+struct QueryPointerResponse {
+    #[prost(string, tag = "1")]
+    pub pointer: prost::alloc::string::String,
+
+    #[prost(uint32, tag = "2")]
+    pub version: u32,
+
+    #[prost(bool, tag = "3")]
+    pub exists: bool,
+}
+
 #[derive(prost::Message)]
 struct QueryContractRequest {
     #[prost(string, tag = "1")]
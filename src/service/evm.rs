@@ -0,0 +1,381 @@
+use super::{ContractInfo, Supply};
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+static NAME_SELECTOR: &str = "0x06fdde03";
+static SYMBOL_SELECTOR: &str = "0x95d89b41";
+static TOTAL_SUPPLY_SELECTOR: &str = "0x18160ddd";
+static TOKEN_URI_SELECTOR: &str = "0xc87b56dd";
+
+pub struct EvmClient {
+    http: reqwest::Client,
+    rpc_url: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum EvmClientError {
+    #[error("Rpc errors : {0}")]
+    Rpc(String),
+
+    #[error("Http Error")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Json Error")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Abi decode error: {0}")]
+    AbiDecode(String),
+}
+
+// evm rpc queries the erc721 stream depends on, kept behind a trait like `ChainClient`
+#[async_trait]
+pub trait EvmChainClient: Send + Sync {
+    async fn get_block_number(&self) -> Result<u64, EvmClientError>;
+
+    async fn get_logs(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        topics: Vec<&str>,
+    ) -> Result<Vec<Log>, EvmClientError>;
+
+    async fn get_erc721_contract_info(&self, address: &str)
+        -> Result<ContractInfo, EvmClientError>;
+
+    async fn get_erc721_total_supply(&self, address: &str) -> Result<Supply, EvmClientError>;
+
+    async fn get_erc721_token_uri(
+        &self,
+        address: &str,
+        token_id: &str,
+    ) -> Result<String, EvmClientError>;
+
+    // sei specific endpoint, fails when the address has not been associated yet
+    async fn get_sei_address(&self, evm_address: &str) -> Result<String, EvmClientError>;
+}
+
+#[async_trait]
+impl EvmChainClient for EvmClient {
+    async fn get_block_number(&self) -> Result<u64, EvmClientError> {
+        let block: String = self.call("eth_blockNumber", json!([])).await?;
+
+        parse_quantity(&block)
+    }
+
+    async fn get_logs(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        topics: Vec<&str>,
+    ) -> Result<Vec<Log>, EvmClientError> {
+        self.call(
+            "eth_getLogs",
+            json!([{
+                "fromBlock": format!("0x{:x}", from_block),
+                "toBlock": format!("0x{:x}", to_block),
+                "topics": topics,
+            }]),
+        )
+        .await
+    }
+
+    async fn get_erc721_contract_info(
+        &self,
+        address: &str,
+    ) -> Result<ContractInfo, EvmClientError> {
+        let name = self.eth_call(address, NAME_SELECTOR.to_owned()).await?;
+        let symbol = self.eth_call(address, SYMBOL_SELECTOR.to_owned()).await?;
+
+        Ok(ContractInfo {
+            name: decode_string(&name)?,
+            symbol: decode_string(&symbol)?,
+        })
+    }
+
+    async fn get_erc721_total_supply(&self, address: &str) -> Result<Supply, EvmClientError> {
+        let supply = self
+            .eth_call(address, TOTAL_SUPPLY_SELECTOR.to_owned())
+            .await?;
+
+        Ok(Supply {
+            count: parse_quantity(&supply)? as u32,
+        })
+    }
+
+    async fn get_erc721_token_uri(
+        &self,
+        address: &str,
+        token_id: &str,
+    ) -> Result<String, EvmClientError> {
+        let data = format!("{}{}", TOKEN_URI_SELECTOR, encode_uint256(token_id)?);
+        let uri = self.eth_call(address, data).await?;
+
+        decode_string(&uri)
+    }
+
+    async fn get_sei_address(&self, evm_address: &str) -> Result<String, EvmClientError> {
+        self.call("sei_getSeiAddress", json!([evm_address])).await
+    }
+}
+
+impl EvmClient {
+    pub fn from(rpc_url: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            rpc_url,
+        }
+    }
+
+    async fn eth_call(&self, address: &str, data: String) -> Result<String, EvmClientError> {
+        self.call(
+            "eth_call",
+            json!([{ "to": address, "data": data }, "latest"]),
+        )
+        .await
+    }
+
+    async fn call<T, U>(&self, method: &str, params: T) -> Result<U, EvmClientError>
+    where
+        T: Serialize,
+        U: DeserializeOwned,
+    {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let res = self
+            .http
+            .post(&self.rpc_url)
+            .json(&body)
+            .send()
+            .await?
+            .json::<RpcResponse>()
+            .await?;
+
+        if let Some(error) = res.error {
+            return Err(EvmClientError::Rpc(error.to_string()));
+        }
+
+        let result = serde_json::from_value::<U>(res.result.unwrap_or_default())?;
+
+        Ok(result)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct RpcResponse {
+    result: Option<Value>,
+    error: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    pub address: String,
+    pub topics: Vec<String>,
+    pub block_number: String,
+    pub transaction_hash: String,
+    pub log_index: String,
+}
+
+pub fn parse_quantity(hex: &str) -> Result<u64, EvmClientError> {
+    let hex = hex.trim_start_matches("0x");
+
+    // abi encoded words are left padded, keep the meaningful tail only
+    let hex = hex.trim_start_matches('0');
+
+    if hex.is_empty() {
+        return Ok(0);
+    }
+
+    u64::from_str_radix(hex, 16).map_err(|e| EvmClientError::AbiDecode(e.to_string()))
+}
+
+// 32 bytes topic -> 20 bytes address
+pub fn topic_to_address(topic: &str) -> String {
+    let topic = topic.trim_start_matches("0x");
+    let start = topic.len().saturating_sub(40);
+
+    format!("0x{}", &topic[start..]).to_lowercase()
+}
+
+// token ids are uint256 so they can not fit into any primitive, convert hex -> decimal digit by digit
+pub fn topic_to_token_id(topic: &str) -> Result<String, EvmClientError> {
+    let hex = topic.trim_start_matches("0x");
+
+    if hex.is_empty() || hex.len() > 64 {
+        return Err(EvmClientError::AbiDecode(format!(
+            "invalid token id topic {}",
+            topic
+        )));
+    }
+
+    let mut digits: Vec<u8> = vec![0];
+
+    for c in hex.chars() {
+        let mut carry = c.to_digit(16).ok_or(EvmClientError::AbiDecode(format!(
+            "invalid token id topic {}",
+            topic
+        )))?;
+
+        for digit in digits.iter_mut() {
+            let value = *digit as u32 * 16 + carry;
+            *digit = (value % 10) as u8;
+            carry = value / 10;
+        }
+
+        while carry > 0 {
+            digits.push((carry % 10) as u8);
+            carry /= 10;
+        }
+    }
+
+    Ok(digits.iter().rev().map(|d| (b'0' + d) as char).collect())
+}
+
+fn encode_uint256(decimal: &str) -> Result<String, EvmClientError> {
+    let invalid = || EvmClientError::AbiDecode(format!("invalid token id {}", decimal));
+
+    if decimal.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut bytes = [0u8; 32];
+
+    for c in decimal.chars() {
+        let mut carry = c.to_digit(10).ok_or_else(invalid)?;
+
+        for byte in bytes.iter_mut().rev() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = (value & 0xff) as u8;
+            carry = value >> 8;
+        }
+
+        // whatever is left did not fit into 256 bits
+        if carry > 0 {
+            return Err(invalid());
+        }
+    }
+
+    Ok(hex::encode(bytes))
+}
+
+// abi encoded string: offset (32 bytes) | ... | length (32 bytes) at the offset | data
+fn decode_string(hex: &str) -> Result<String, EvmClientError> {
+    let bytes = hex::decode(hex.trim_start_matches("0x"))
+        .map_err(|e| EvmClientError::AbiDecode(e.to_string()))?;
+
+    let offset = decode_word(&bytes, 0)?;
+    let length = decode_word(&bytes, offset)?;
+
+    let data = offset
+        .checked_add(32)
+        .and_then(|start| bytes.get(start..))
+        .and_then(|data| data.get(..length))
+        .ok_or(EvmClientError::AbiDecode(format!(
+            "string output is shorter than {} bytes",
+            length
+        )))?;
+
+    String::from_utf8(data.to_vec()).map_err(|e| EvmClientError::AbiDecode(e.to_string()))
+}
+
+// an offset or a length, a word that does not fit into usize can not point into the output
+fn decode_word(bytes: &[u8], at: usize) -> Result<usize, EvmClientError> {
+    let word = at
+        .checked_add(32)
+        .and_then(|end| bytes.get(at..end))
+        .ok_or(EvmClientError::AbiDecode(format!(
+            "output has no word at byte {}",
+            at
+        )))?;
+
+    let (high, low) = word.split_at(32 - std::mem::size_of::<usize>());
+
+    if high.iter().any(|byte| *byte != 0) {
+        return Err(EvmClientError::AbiDecode(format!(
+            "word at byte {} is out of range",
+            at
+        )));
+    }
+
+    Ok(low
+        .iter()
+        .fold(0usize, |acc, byte| (acc << 8) | *byte as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static MAX_UINT256: &str =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+    // a word per argument, abi encoding is hex of 32 byte words
+    fn word(value: usize) -> String {
+        format!("{:064x}", value)
+    }
+
+    #[test]
+    fn token_id_round_trips_through_the_topic() {
+        for token_id in ["0", "1", "255", "1234567890"] {
+            let topic = format!("0x{}", encode_uint256(token_id).unwrap());
+
+            assert_eq!(topic_to_token_id(&topic).unwrap(), token_id);
+        }
+    }
+
+    #[test]
+    fn token_id_keeps_every_digit_of_a_large_uint256() {
+        let topic = format!("0x{}", "f".repeat(64));
+
+        assert_eq!(topic_to_token_id(&topic).unwrap(), MAX_UINT256);
+        assert_eq!(encode_uint256(MAX_UINT256).unwrap(), "f".repeat(64));
+    }
+
+    #[test]
+    fn token_id_out_of_uint256_is_an_error() {
+        // 2^256
+        let overflow =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+
+        assert!(encode_uint256(overflow).is_err());
+        assert!(topic_to_token_id(&format!("0x1{}", "0".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn malformed_hex_is_an_error() {
+        assert!(topic_to_token_id("0x").is_err());
+        assert!(topic_to_token_id("0x12zz").is_err());
+        assert!(encode_uint256("").is_err());
+        assert!(encode_uint256("12a").is_err());
+        assert!(decode_string("0x").is_err());
+        assert!(decode_string("0x1234").is_err());
+        assert!(decode_string("0xzz").is_err());
+    }
+
+    #[test]
+    fn string_is_read_at_its_offset() {
+        let data = hex::encode("hello");
+
+        let output = format!("0x{}{}{:0<64}", word(32), word(5), data);
+        assert_eq!(decode_string(&output).unwrap(), "hello");
+
+        // the offset is followed, not assumed to be the next word
+        let output = format!("0x{}{}{}{:0<64}", word(64), word(0), word(5), data);
+        assert_eq!(decode_string(&output).unwrap(), "hello");
+    }
+
+    #[test]
+    fn string_longer_than_the_output_is_an_error() {
+        let output = format!("0x{}{}{:0<64}", word(32), word(33), hex::encode("hello"));
+        assert!(decode_string(&output).is_err());
+
+        let output = format!("0x{}{}", word(usize::MAX), word(5));
+        assert!(decode_string(&output).is_err());
+    }
+}
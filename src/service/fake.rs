@@ -0,0 +1,354 @@
+use super::{
+    ChainClient, CollectionMetadata, CollectionMetadataProvider, ContractInfo, CosmosClientError,
+    EvmChainClient, EvmClientError, Log, MetadataError, NftInfo, NftMetadata, NftMetadataProvider,
+    PalletListing, PointerType, RoyaltyInfo, Supply,
+};
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tendermint_rpc::{
+    endpoint::{tx, tx_search},
+    query::Query,
+};
+
+// in-memory doubles for the stream handlers, each one is a cheap handle over shared state
+// so a scenario can keep a clone and change the chain between two transactions
+
+#[derive(Default)]
+struct FakeChainState {
+    contracts: HashMap<String, (ContractInfo, Supply)>,
+    nfts: HashMap<(String, String), NftInfo>,
+    royalties: HashMap<String, (String, u128)>,
+    pallet_listings: HashMap<(String, String), PalletListing>,
+    txs: HashMap<String, tx::Response>,
+    searchable_txs: Vec<tx::Response>,
+    pointers: HashMap<(PointerType, String), String>,
+}
+
+#[derive(Clone, Default)]
+pub struct FakeChainClient(Arc<Mutex<FakeChainState>>);
+
+impl FakeChainClient {
+    pub fn with_contract(self, address: &str, info: ContractInfo, supply: Supply) -> Self {
+        self.state()
+            .contracts
+            .insert(address.to_owned(), (info, supply));
+        self
+    }
+
+    pub fn with_nft(self, address: &str, token_id: &str, info: NftInfo) -> Self {
+        self.state()
+            .nfts
+            .insert((address.to_owned(), token_id.to_owned()), info);
+        self
+    }
+
+    // cw2981 royalty of every token of the contract, as a whole percentage
+    pub fn with_royalty(self, address: &str, payment_address: &str, percentage: u128) -> Self {
+        self.state()
+            .royalties
+            .insert(address.to_owned(), (payment_address.to_owned(), percentage));
+        self
+    }
+
+    // registered for one direction only, asking the other pointer type finds nothing like on chain
+    pub fn with_pointer(self, pointer_type: PointerType, pointee: &str, pointer: &str) -> Self {
+        self.state()
+            .pointers
+            .insert((pointer_type, pointee.to_owned()), pointer.to_owned());
+        self
+    }
+
+    pub fn set_pallet_listing(&self, address: &str, token_id: &str, listing: PalletListing) {
+        self.state()
+            .pallet_listings
+            .insert((address.to_owned(), token_id.to_owned()), listing);
+    }
+
+    pub fn set_tx(&self, tx_hash: &str, tx: tx::Response) {
+        self.state().txs.insert(tx_hash.to_owned(), tx);
+    }
+
+    // returned by `search_tx` in insertion order, the query itself is not evaluated
+    pub fn push_searchable_tx(&self, tx: tx::Response) {
+        self.state().searchable_txs.push(tx);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, FakeChainState> {
+        self.0.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl ChainClient for FakeChainClient {
+    async fn get_cw721_contract_info(
+        &self,
+        address: &str,
+    ) -> Result<ContractInfo, CosmosClientError> {
+        self.state()
+            .contracts
+            .get(address)
+            .map(|(info, _)| info.to_owned())
+            .ok_or(not_found("contract", address))
+    }
+
+    async fn get_cw721_contract_supply(&self, address: &str) -> Result<Supply, CosmosClientError> {
+        self.state()
+            .contracts
+            .get(address)
+            .map(|(_, supply)| supply.to_owned())
+            .ok_or(not_found("contract", address))
+    }
+
+    async fn get_nft_info(
+        &self,
+        address: &str,
+        token_id: &str,
+    ) -> Result<NftInfo, CosmosClientError> {
+        self.state()
+            .nfts
+            .get(&(address.to_owned(), token_id.to_owned()))
+            .cloned()
+            .ok_or(not_found("nft", token_id))
+    }
+
+    async fn get_royalty_info(
+        &self,
+        address: &str,
+        _token_id: &str,
+        sale_price: u128,
+    ) -> Result<RoyaltyInfo, CosmosClientError> {
+        self.state()
+            .royalties
+            .get(address)
+            .map(|(payment_address, percentage)| RoyaltyInfo {
+                address: payment_address.to_owned(),
+                royalty_amount: (sale_price * percentage / 100).to_string(),
+            })
+            .ok_or(not_found("royalty", address))
+    }
+
+    async fn get_pallet_listing(
+        &self,
+        token_address: &str,
+        token_id: &str,
+    ) -> Result<PalletListing, CosmosClientError> {
+        self.state()
+            .pallet_listings
+            .get(&(token_address.to_owned(), token_id.to_owned()))
+            .cloned()
+            .ok_or(not_found("pallet listing", token_id))
+    }
+
+    async fn get_tx(&self, tx_hash: &str) -> Result<tx::Response, CosmosClientError> {
+        self.state()
+            .txs
+            .get(tx_hash)
+            .cloned()
+            .ok_or(not_found("tx", tx_hash))
+    }
+
+    async fn search_tx(
+        &self,
+        _query: Query,
+        page: u32,
+        per_page: u8,
+    ) -> Result<tx_search::Response, CosmosClientError> {
+        let txs = &self.state().searchable_txs;
+        let per_page = per_page as usize;
+
+        Ok(tx_search::Response {
+            txs: txs
+                .iter()
+                .skip(page.saturating_sub(1) as usize * per_page)
+                .take(per_page)
+                .cloned()
+                .collect(),
+            total_count: txs.len() as u32,
+        })
+    }
+
+    async fn get_pointer(
+        &self,
+        pointer_type: PointerType,
+        pointee: &str,
+    ) -> Result<Option<String>, CosmosClientError> {
+        Ok(self
+            .state()
+            .pointers
+            .get(&(pointer_type, pointee.to_owned()))
+            .cloned())
+    }
+}
+
+#[derive(Default)]
+struct FakeEvmState {
+    block_number: u64,
+    logs: Vec<Log>,
+    contracts: HashMap<String, (ContractInfo, Supply)>,
+    token_uris: HashMap<(String, String), String>,
+    sei_addresses: HashMap<String, String>,
+}
+
+#[derive(Clone, Default)]
+pub struct FakeEvmClient(Arc<Mutex<FakeEvmState>>);
+
+impl FakeEvmClient {
+    pub fn with_contract(self, address: &str, info: ContractInfo, supply: Supply) -> Self {
+        self.state()
+            .contracts
+            .insert(address.to_owned(), (info, supply));
+        self
+    }
+
+    pub fn with_token_uri(self, address: &str, token_id: &str, token_uri: &str) -> Self {
+        self.state().token_uris.insert(
+            (address.to_owned(), token_id.to_owned()),
+            token_uri.to_owned(),
+        );
+        self
+    }
+
+    // addresses without an association are answered with an rpc error like sei does
+    pub fn with_sei_address(self, evm_address: &str, sei_address: &str) -> Self {
+        self.state()
+            .sei_addresses
+            .insert(evm_address.to_owned(), sei_address.to_owned());
+        self
+    }
+
+    // the log block number becomes the chain head
+    pub fn push_log(&self, log: Log) {
+        let mut state = self.state();
+
+        let block_number = super::parse_quantity(&log.block_number).unwrap_or_default();
+        state.block_number = state.block_number.max(block_number);
+        state.logs.push(log);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, FakeEvmState> {
+        self.0.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl EvmChainClient for FakeEvmClient {
+    async fn get_block_number(&self) -> Result<u64, EvmClientError> {
+        Ok(self.state().block_number)
+    }
+
+    // topics are not evaluated, only the block range is
+    async fn get_logs(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        _topics: Vec<&str>,
+    ) -> Result<Vec<Log>, EvmClientError> {
+        Ok(self
+            .state()
+            .logs
+            .iter()
+            .filter(|log| {
+                let block_number = super::parse_quantity(&log.block_number).unwrap_or_default();
+                (from_block..=to_block).contains(&block_number)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn get_erc721_contract_info(
+        &self,
+        address: &str,
+    ) -> Result<ContractInfo, EvmClientError> {
+        self.state()
+            .contracts
+            .get(address)
+            .map(|(info, _)| info.to_owned())
+            .ok_or(evm_not_found("contract", address))
+    }
+
+    async fn get_erc721_total_supply(&self, address: &str) -> Result<Supply, EvmClientError> {
+        self.state()
+            .contracts
+            .get(address)
+            .map(|(_, supply)| supply.to_owned())
+            .ok_or(evm_not_found("contract", address))
+    }
+
+    async fn get_erc721_token_uri(
+        &self,
+        address: &str,
+        token_id: &str,
+    ) -> Result<String, EvmClientError> {
+        self.state()
+            .token_uris
+            .get(&(address.to_owned(), token_id.to_owned()))
+            .cloned()
+            .ok_or(evm_not_found("token uri", token_id))
+    }
+
+    async fn get_sei_address(&self, evm_address: &str) -> Result<String, EvmClientError> {
+        self.state()
+            .sei_addresses
+            .get(evm_address)
+            .cloned()
+            .ok_or(evm_not_found("sei address", evm_address))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct FakeNftMetadata(Arc<Mutex<HashMap<String, NftMetadata>>>);
+
+impl FakeNftMetadata {
+    pub fn with_metadata(self, uri: &str, metadata: NftMetadata) -> Self {
+        self.0.lock().unwrap().insert(uri.to_owned(), metadata);
+        self
+    }
+}
+
+#[async_trait]
+impl NftMetadataProvider for FakeNftMetadata {
+    async fn get_nft_metadata(&self, uri: &str) -> Result<NftMetadata, MetadataError> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(uri)
+            .cloned()
+            .ok_or(MetadataError::NotFound(uri.to_owned()))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct FakeCollectionMetadata(Arc<Mutex<HashMap<String, CollectionMetadata>>>);
+
+impl FakeCollectionMetadata {
+    pub fn with_metadata(self, address: &str, metadata: CollectionMetadata) -> Self {
+        self.0.lock().unwrap().insert(address.to_owned(), metadata);
+        self
+    }
+}
+
+#[async_trait]
+impl CollectionMetadataProvider for FakeCollectionMetadata {
+    async fn get_collection_metadata(
+        &self,
+        address: &str,
+    ) -> Result<CollectionMetadata, MetadataError> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(address)
+            .cloned()
+            .ok_or(MetadataError::NotFound(address.to_owned()))
+    }
+}
+
+fn not_found(kind: &str, key: &str) -> CosmosClientError {
+    CosmosClientError::RpcError(format!("{} {} not found", kind, key))
+}
+
+fn evm_not_found(kind: &str, key: &str) -> EvmClientError {
+    EvmClientError::Rpc(format!("{} {} not found", kind, key))
+}
@@ -1,7 +1,10 @@
+use super::MetadataError;
 use crate::r#static::PALLET_API_URL;
+use async_trait::async_trait;
+use reqwest::StatusCode;
 use serde::Deserialize;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct CollectionMetadata {
     pub pfp: Option<String>,
     pub slug: Option<String>,
@@ -10,11 +13,31 @@ pub struct CollectionMetadata {
     pub socials: Option<serde_json::Value>,
 }
 
-pub async fn get_collection_metadata(address: &str) -> Result<CollectionMetadata, reqwest::Error> {
-    let endpoint = format!("{}/v2/nfts/{address}/details", PALLET_API_URL);
+#[async_trait]
+pub trait CollectionMetadataProvider: Send + Sync {
+    async fn get_collection_metadata(
+        &self,
+        address: &str,
+    ) -> Result<CollectionMetadata, MetadataError>;
+}
+
+// pallet already curates pfp, banner and socials for most collections on sei
+pub struct PalletCollectionMetadata;
+
+#[async_trait]
+impl CollectionMetadataProvider for PalletCollectionMetadata {
+    async fn get_collection_metadata(
+        &self,
+        address: &str,
+    ) -> Result<CollectionMetadata, MetadataError> {
+        let endpoint = format!("{}/v2/nfts/{address}/details", PALLET_API_URL);
+
+        let res = reqwest::get(endpoint).await?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Err(MetadataError::NotFound(address.to_owned()));
+        }
 
-    reqwest::get(endpoint)
-        .await?
-        .json::<CollectionMetadata>()
-        .await
+        Ok(res.json::<CollectionMetadata>().await?)
+    }
 }
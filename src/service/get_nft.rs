@@ -1,4 +1,7 @@
-use serde::Deserialize;
+use super::MetadataError;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -17,6 +20,50 @@ pub struct NftAttribute {
     pub display_type: Option<Value>,
 }
 
-pub async fn get_nft_metadata(uri: &str) -> Result<NftMetadata, reqwest::Error> {
-    reqwest::get(uri).await?.json::<NftMetadata>().await
+// how an attribute is stored, metadata names it either trait_type or type
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct NftTrait {
+    pub attribute: String,
+    pub value: String,
+    pub display_type: Option<String>,
+}
+
+impl From<NftAttribute> for NftTrait {
+    fn from(
+        NftAttribute {
+            trait_type,
+            r#type,
+            value,
+            display_type,
+        }: NftAttribute,
+    ) -> Self {
+        Self {
+            attribute: trait_type.unwrap_or(r#type.unwrap_or("unknown".to_string())),
+            value: value
+                .map(|v| v.to_string())
+                .unwrap_or("unknown".to_string()),
+            display_type: display_type.map(|v| v.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+pub trait NftMetadataProvider: Send + Sync {
+    async fn get_nft_metadata(&self, uri: &str) -> Result<NftMetadata, MetadataError>;
+}
+
+// resolves metadata by fetching the token uri itself
+pub struct TokenUriMetadata;
+
+#[async_trait]
+impl NftMetadataProvider for TokenUriMetadata {
+    async fn get_nft_metadata(&self, uri: &str) -> Result<NftMetadata, MetadataError> {
+        let res = reqwest::get(uri).await?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Err(MetadataError::NotFound(uri.to_owned()));
+        }
+
+        Ok(res.json::<NftMetadata>().await?)
+    }
 }
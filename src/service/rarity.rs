@@ -0,0 +1,229 @@
+use crate::database::{model::NftTraitValue, RarityMethod};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+// an nft without an attribute of its collection counts as having the value None for it
+static NONE_VALUE: &str = "None";
+// how many traits an nft has is rare in itself, so it is scored like any other attribute
+static TRAIT_COUNT_ATTRIBUTE: &str = "Trait Count";
+// scores closer than this share a rank, they only differ by float rounding
+static SCORE_EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone)]
+pub struct RarityScore {
+    pub nft_id: i32,
+    pub method: RarityMethod,
+    pub score: f64,
+    pub rank: i32,
+}
+
+// every method scores higher for rarer nfts, rank 1 is the rarest and equal scores share a rank
+pub fn compute_rarity_scores(nft_ids: &[i32], traits: Vec<NftTraitValue>) -> Vec<RarityScore> {
+    if nft_ids.is_empty() {
+        return Vec::new();
+    }
+
+    let supply = nft_ids.len() as f64;
+
+    let mut nft_traits: HashMap<i32, BTreeMap<String, String>> = HashMap::new();
+    let mut attributes = BTreeSet::new();
+
+    for NftTraitValue {
+        nft_id,
+        attribute,
+        value,
+    } in traits
+    {
+        attributes.insert(attribute.to_owned());
+        nft_traits
+            .entry(nft_id)
+            .or_default()
+            .entry(attribute)
+            .or_insert(value);
+    }
+
+    let nft_traits: Vec<(i32, BTreeMap<String, String>)> = nft_ids
+        .iter()
+        .map(|nft_id| {
+            let mut values = nft_traits.remove(nft_id).unwrap_or_default();
+            let trait_count = values.len();
+
+            for attribute in &attributes {
+                values
+                    .entry(attribute.to_owned())
+                    .or_insert(NONE_VALUE.to_owned());
+            }
+
+            values.insert(TRAIT_COUNT_ATTRIBUTE.to_owned(), trait_count.to_string());
+
+            (*nft_id, values)
+        })
+        .collect();
+
+    let mut counts: HashMap<(&str, &str), f64> = HashMap::new();
+
+    for (_, values) in &nft_traits {
+        for (attribute, value) in values {
+            *counts.entry((attribute, value)).or_default() += 1.0;
+        }
+    }
+
+    let mut category_sizes: HashMap<&str, f64> = HashMap::new();
+
+    for (attribute, _) in counts.keys() {
+        *category_sizes.entry(attribute).or_default() += 1.0;
+    }
+
+    let average_category_size = category_sizes.values().sum::<f64>() / category_sizes.len() as f64;
+
+    let entropy: f64 = counts
+        .values()
+        .map(|count| {
+            let probability = count / supply;
+            -probability * probability.log2()
+        })
+        .sum();
+
+    let mut scores = Vec::new();
+
+    for method in [
+        RarityMethod::Statistical,
+        RarityMethod::TraitRaritySum,
+        RarityMethod::InformationContent,
+    ] {
+        let method_scores: Vec<(i32, f64)> = nft_traits
+            .iter()
+            .map(|(nft_id, values)| {
+                let probabilities = values.iter().map(|(attribute, value)| {
+                    (
+                        attribute.as_str(),
+                        counts[&(attribute.as_str(), value.as_str())] / supply,
+                    )
+                });
+
+                let score = match method {
+                    // the log keeps the product of probabilities from overflowing on large attribute sets
+                    RarityMethod::Statistical => probabilities
+                        .map(|(_, probability)| -probability.ln())
+                        .sum(),
+                    // attributes with few values would dominate otherwise, so they are normalized by their size
+                    RarityMethod::TraitRaritySum => probabilities
+                        .map(|(attribute, probability)| {
+                            (1.0 / probability) * average_category_size / category_sizes[attribute]
+                        })
+                        .sum(),
+                    RarityMethod::InformationContent if entropy > 0.0 => {
+                        probabilities
+                            .map(|(_, probability)| -probability.log2())
+                            .sum::<f64>()
+                            / entropy
+                    }
+                    RarityMethod::InformationContent => 0.0,
+                };
+
+                (*nft_id, score)
+            })
+            .collect();
+
+        scores.extend(rank(method, method_scores));
+    }
+
+    scores
+}
+
+fn rank(method: RarityMethod, mut scores: Vec<(i32, f64)>) -> Vec<RarityScore> {
+    scores.sort_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then(a_id.cmp(b_id)));
+
+    let mut ranked: Vec<RarityScore> = Vec::with_capacity(scores.len());
+
+    for (index, (nft_id, score)) in scores.into_iter().enumerate() {
+        let rank = match ranked.last() {
+            Some(previous) if (previous.score - score).abs() < SCORE_EPSILON => previous.rank,
+            _ => index as i32 + 1,
+        };
+
+        ranked.push(RarityScore {
+            nft_id,
+            method: method.to_owned(),
+            score,
+            rank,
+        });
+    }
+
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn traits(values: &[(i32, &str, &str)]) -> Vec<NftTraitValue> {
+        values
+            .iter()
+            .map(|(nft_id, attribute, value)| NftTraitValue {
+                nft_id: *nft_id,
+                attribute: attribute.to_string(),
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    fn score_of(scores: &[RarityScore], method: RarityMethod, nft_id: i32) -> &RarityScore {
+        scores
+            .iter()
+            .find(|score| score.method == method && score.nft_id == nft_id)
+            .unwrap()
+    }
+
+    #[test]
+    fn rarest_nft_ranks_first_and_equal_scores_share_a_rank() {
+        let scores = compute_rarity_scores(
+            &[1, 2, 3, 4],
+            traits(&[
+                (1, "Background", "Blue"),
+                (2, "Background", "Blue"),
+                (3, "Background", "Blue"),
+                (4, "Background", "Gold"),
+            ]),
+        );
+
+        for method in [
+            RarityMethod::Statistical,
+            RarityMethod::TraitRaritySum,
+            RarityMethod::InformationContent,
+        ] {
+            assert_eq!(score_of(&scores, method.to_owned(), 4).rank, 1);
+
+            for nft_id in [1, 2, 3] {
+                assert_eq!(score_of(&scores, method.to_owned(), nft_id).rank, 2);
+            }
+        }
+    }
+
+    #[test]
+    fn missing_trait_counts_as_its_own_value() {
+        let scores = compute_rarity_scores(
+            &[1, 2, 3, 4],
+            traits(&[(1, "Hat", "Cap"), (2, "Hat", "Cap"), (3, "Hat", "Crown")]),
+        );
+
+        // Hat None and Trait Count 0 are each held by nft 4 alone
+        let score = score_of(&scores, RarityMethod::Statistical, 4);
+
+        assert!((score.score - 2.0 * 4f64.ln()).abs() < SCORE_EPSILON);
+        assert_eq!(score.rank, 1);
+
+        // Hat Crown is as rare as Hat None, only the Trait Count of 1 is shared with nfts 1 and 2
+        let score = score_of(&scores, RarityMethod::Statistical, 3);
+
+        assert!((score.score - (4f64.ln() - 0.75f64.ln())).abs() < SCORE_EPSILON);
+        assert_eq!(score.rank, 2);
+    }
+
+    #[test]
+    fn collection_without_traits_scores_every_nft_the_same() {
+        let scores = compute_rarity_scores(&[1, 2], Vec::new());
+
+        assert_eq!(scores.len(), 6);
+        assert!(scores.iter().all(|score| score.rank == 1));
+    }
+}
@@ -0,0 +1,142 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bech32::{Bech32, Hrp};
+use k256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use ripemd::Ripemd160;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+static SEI_PREFIX: &str = "sei";
+
+#[derive(thiserror::Error, Debug)]
+pub enum SignatureError {
+    #[error("Invalid encoding: {0}")]
+    Encoding(#[from] base64::DecodeError),
+
+    #[error("Invalid public key")]
+    PublicKey,
+
+    #[error("Invalid signature")]
+    Signature,
+
+    #[error("Invalid address: {0}")]
+    Address(String),
+
+    #[error("Signer {0} does not own the public key")]
+    Signer(String),
+}
+
+// wallets sign arbitrary data as an amino StdSignDoc wrapping a MsgSignData, see ADR-036
+pub fn verify_adr036_signature(
+    signer: &str,
+    data: &str,
+    pub_key: &str,
+    signature: &str,
+) -> Result<(), SignatureError> {
+    let (hrp, _) = bech32::decode(signer).map_err(|e| SignatureError::Address(e.to_string()))?;
+
+    if hrp.as_str() != SEI_PREFIX {
+        return Err(SignatureError::Address(signer.to_owned()));
+    }
+
+    let pub_key = BASE64_STANDARD.decode(pub_key)?;
+
+    if pub_key_to_address(&pub_key)? != signer {
+        return Err(SignatureError::Signer(signer.to_owned()));
+    }
+
+    let verifying_key =
+        VerifyingKey::from_sec1_bytes(&pub_key).map_err(|_| SignatureError::PublicKey)?;
+
+    let signature = Signature::from_slice(&BASE64_STANDARD.decode(signature)?)
+        .map_err(|_| SignatureError::Signature)?;
+
+    // cosmos only accepts low-s signatures, the mirrored high-s one is a second encoding of it
+    if signature.normalize_s().is_some() {
+        return Err(SignatureError::Signature);
+    }
+
+    // the sign doc is hashed with sha256 by the verifier, keys must stay sorted like amino json does
+    let sign_doc = json!({
+        "account_number": "0",
+        "chain_id": "",
+        "fee": { "amount": [], "gas": "0" },
+        "memo": "",
+        "msgs": [{
+            "type": "sign/MsgSignData",
+            "value": {
+                "data": BASE64_STANDARD.encode(data),
+                "signer": signer,
+            },
+        }],
+        "sequence": "0",
+    });
+
+    verifying_key
+        .verify(sign_doc.to_string().as_bytes(), &signature)
+        .map_err(|_| SignatureError::Signature)
+}
+
+fn pub_key_to_address(pub_key: &[u8]) -> Result<String, SignatureError> {
+    let hash = Ripemd160::digest(Sha256::digest(pub_key));
+    let hrp = Hrp::parse(SEI_PREFIX).map_err(|e| SignatureError::Address(e.to_string()))?;
+
+    bech32::encode::<Bech32>(hrp, &hash).map_err(|e| SignatureError::Address(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // signed independently of this module with a throwaway key, over the amino json sign doc
+    static SIGNER: &str = "sei1atr3f7st7tugrg5mgp5ga6yap37f9x9krly3ye";
+    static PUB_KEY: &str = "A+aKz8AlOhBiDf9wawobHx9YM+o76zveIlDV8nHzVjYG";
+    static DATA: &str = "Sign in to Oxide Sei Market\n\nNonce: 3f1c9a7e5b2d4c6f";
+    static SIGNATURE: &str =
+        "qy4XcSsj6WyHtcNpsYWDgezZXMPgQR2QIJhbeLdzXRItqQ9bgC6jXtjCjriDI19I8jJAeMA8DEOX3fMKGYmyeg==";
+
+    #[test]
+    fn accepts_a_signature_of_the_signer() {
+        assert!(verify_adr036_signature(SIGNER, DATA, PUB_KEY, SIGNATURE).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_signer_that_does_not_own_the_public_key() {
+        let other = "sei1rcnfzetz9z8q4q22dhk2pgjm4v485cuallj47c";
+
+        assert!(matches!(
+            verify_adr036_signature(other, DATA, PUB_KEY, SIGNATURE),
+            Err(SignatureError::Signer(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_data() {
+        let data = DATA.replace("3f1c9a7e5b2d4c6f", "3f1c9a7e5b2d4c60");
+
+        assert!(matches!(
+            verify_adr036_signature(SIGNER, &data, PUB_KEY, SIGNATURE),
+            Err(SignatureError::Signature)
+        ));
+    }
+
+    #[test]
+    fn rejects_an_address_of_another_chain() {
+        let signer = "cosmos1atr3f7st7tugrg5mgp5ga6yap37f9x9kwn48zc";
+
+        assert!(matches!(
+            verify_adr036_signature(signer, DATA, PUB_KEY, SIGNATURE),
+            Err(SignatureError::Address(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_a_high_s_signature() {
+        let signature =
+            "qy4XcSsj6WyHtcNpsYWDgezZXMPgQR2QIJhbeLdzXRLSVvCkf9FcoSc9cUd83KC1yHycbe8Mk/gn9GuCtqyOxw==";
+
+        assert!(matches!(
+            verify_adr036_signature(SIGNER, DATA, PUB_KEY, signature),
+            Err(SignatureError::Signature)
+        ));
+    }
+}
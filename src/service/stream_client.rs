@@ -0,0 +1,54 @@
+use super::{
+    ChainClient, CollectionMetadataProvider, CosmosClient, EvmChainClient, NftMetadataProvider,
+    PalletCollectionMetadata, TokenUriMetadata,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum MetadataError {
+    #[error("HttpRequest error: {0}")]
+    HttpRequest(#[from] reqwest::Error),
+
+    #[error("Metadata not found: {0}")]
+    NotFound(String),
+}
+
+// everything the stream handlers read from outside the database
+pub struct StreamClient {
+    pub chain: Box<dyn ChainClient>,
+    pub nft_metadata: Box<dyn NftMetadataProvider>,
+    pub collection_metadata: Box<dyn CollectionMetadataProvider>,
+    // evm only collections have no cw721 contract, their token uris are asked from the erc721 one
+    pub evm: Option<Box<dyn EvmChainClient>>,
+    // responses cached by the server, dropped when a handler changes the data they were built from
+    pub cache: Option<deadpool_redis::Pool>,
+}
+
+impl StreamClient {
+    pub fn new(
+        chain: impl ChainClient + 'static,
+        nft_metadata: impl NftMetadataProvider + 'static,
+        collection_metadata: impl CollectionMetadataProvider + 'static,
+    ) -> Self {
+        Self {
+            chain: Box::new(chain),
+            nft_metadata: Box::new(nft_metadata),
+            collection_metadata: Box::new(collection_metadata),
+            evm: None,
+            cache: None,
+        }
+    }
+
+    pub fn with_evm(mut self, evm: impl EvmChainClient + 'static) -> Self {
+        self.evm = Some(Box::new(evm));
+        self
+    }
+
+    pub fn with_cache(mut self, pool: deadpool_redis::Pool) -> Self {
+        self.cache = Some(pool);
+        self
+    }
+
+    pub fn from(cosmos_client: CosmosClient) -> Self {
+        Self::new(cosmos_client, TokenUriMetadata, PalletCollectionMetadata)
+    }
+}
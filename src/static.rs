@@ -6,3 +6,6 @@ pub static PALLET_CONTRACT_ADDRESS: &'static str =
 #[allow(dead_code)]
 pub static MRKT_CONTRACT_ADDRESS: &'static str =
     "sei1dkp90y3jpp2dres2ssp5rak2k6mc7l4nsxz58nktxjsxqp88fcasmrr672";
+
+// redis pub/sub channel the watcher publishes new activities on, every server instance pushes them to its clients
+pub static ACTIVITY_EVENTS_CHANNEL: &str = "activity_events";
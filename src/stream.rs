@@ -1,114 +1,203 @@
 pub mod cw721;
+pub mod erc721;
+pub mod launchpad;
+pub mod metadata;
 pub mod mrkt;
 pub mod pallet;
 mod shared;
+mod subscription;
 
 use self::shared::Transaction;
-use crate::{error::AppError, r#static::PALLET_CONTRACT_ADDRESS, service::CosmosClient};
-use futures_util::{SinkExt, StreamExt};
-use sea_orm::{ConnectOptions, Database, DatabaseConnection};
-use serde_json::Value;
-use std::future::Future;
+use self::subscription::{Subscriptions, TxHandler};
+use crate::{
+    database::{self, repository::config as ConfigRepository},
+    error::AppError,
+    r#static::PALLET_CONTRACT_ADDRESS,
+    server::create_redis_pool,
+    service::{CosmosClient, EvmChainClient, EvmClient, StreamClient},
+};
+use sea_orm::DatabaseConnection;
+use std::time::Duration;
 use tendermint_rpc::query::{EventType, Query};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
 
-static INGORE_MESSAGE: &'static str = "{\"jsonrpc\":\"2.0\",\"id\":\"0\",\"result\":{}}";
+static ERC721_STREAM_BLOCK: &str = "erc721_stream_block";
+static EVM_BLOCK_RANGE: u64 = 100;
+static EVM_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 pub async fn cw721_stream() {
     dotenv::dotenv().ok();
-    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
-    let rpc_url = std::env::var("RPC_URL").expect("rpc_url must be set");
-    let cosmos_client =
-        CosmosClient::from(tendermint_rpc::HttpClient::new(rpc_url.as_str()).unwrap());
+    run_cw721_stream(database::connect().await).await
+}
 
-    let mut opt = ConnectOptions::new(db_url);
-    opt.sqlx_logging(false);
+pub async fn run_cw721_stream(db: DatabaseConnection) {
+    let client = create_stream_client();
 
-    let db = Database::connect(opt).await.unwrap();
+    Subscriptions::new()
+        .add_subscription("cw721", cw721_query(), &|db, client, tx| {
+            Box::pin(cw721::tx_handler(db, client, tx))
+        })
+        .start(&db, &client)
+        .await;
+}
 
-    let query = Query::from(EventType::Tx)
-        .and_exists("wasm.action")
-        .and_exists("wasm._contract_address")
-        .and_exists("wasm.token_id");
+pub async fn pallet_stream() {
+    dotenv::dotenv().ok();
+    run_pallet_stream(database::connect().await).await
+}
 
-    let msg = create_subcribe_message(query);
+pub async fn run_pallet_stream(db: DatabaseConnection) {
+    let client = create_stream_client();
 
-    loop {
-        if let Err(error) = stream_handler(&db, &cosmos_client, &msg, cw721::tx_handler).await {
-            eprintln!("{}", error)
-        }
-    }
+    Subscriptions::new()
+        .add_subscription("pallet", pallet_query(), &|db, client, tx| {
+            Box::pin(pallet::tx_handler(db, client, tx))
+        })
+        .start(&db, &client)
+        .await;
 }
 
-pub async fn pallet_stream() {
+pub async fn launchpad_stream() {
     dotenv::dotenv().ok();
-    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
-    let rpc_url = std::env::var("RPC_URL").expect("rpc_url must be set");
-    let cosmos_client =
-        CosmosClient::from(tendermint_rpc::HttpClient::new(rpc_url.as_str()).unwrap());
+    run_launchpad_stream(database::connect().await).await
+}
 
-    let mut opt = ConnectOptions::new(db_url);
-    opt.sqlx_logging(false);
+pub async fn run_launchpad_stream(db: DatabaseConnection) {
+    let launchpad_address = std::env::var("LAUNCHPAD_CONTRACT_ADDRESS")
+        .expect("launchpad_contract_address must be set");
+    let client = create_stream_client();
+
+    // handlers can not borrow from the stream setup, the address is owned by the closure
+    let address = launchpad_address.to_owned();
+    let launchpad_handler: &TxHandler =
+        &move |db, client, tx| Box::pin(launchpad::tx_handler(db, client, tx, address.to_owned()));
+
+    Subscriptions::new()
+        .add_subscription(
+            "launchpad",
+            launchpad_query(launchpad_address),
+            launchpad_handler,
+        )
+        .start(&db, &client)
+        .await;
+}
 
-    let db = Database::connect(opt).await.unwrap();
+// cw721, pallet and launchpad (when configured) over a single websocket
+pub async fn contract_stream() {
+    dotenv::dotenv().ok();
+    run_contract_stream(database::connect().await).await
+}
 
-    let query =
-        Query::from(EventType::Tx).and_eq("execute._contract_address", PALLET_CONTRACT_ADDRESS);
+pub async fn run_contract_stream(db: DatabaseConnection) {
+    let launchpad_address = std::env::var("LAUNCHPAD_CONTRACT_ADDRESS").ok();
+    let client = create_stream_client();
+
+    let address = launchpad_address.to_owned().unwrap_or_default();
+    let launchpad_handler: &TxHandler =
+        &move |db, client, tx| Box::pin(launchpad::tx_handler(db, client, tx, address.to_owned()));
+
+    let mut subscriptions = Subscriptions::new()
+        .add_subscription("cw721", cw721_query(), &|db, client, tx| {
+            Box::pin(cw721::tx_handler(db, client, tx))
+        })
+        .add_subscription("pallet", pallet_query(), &|db, client, tx| {
+            Box::pin(pallet::tx_handler(db, client, tx))
+        });
+
+    if let Some(launchpad_address) = launchpad_address {
+        subscriptions = subscriptions.add_subscription(
+            "launchpad",
+            launchpad_query(launchpad_address),
+            launchpad_handler,
+        );
+    }
 
-    let msg = create_subcribe_message(query);
+    subscriptions.start(&db, &client).await;
+}
+
+// evm logs are not pushed through the tendermint websocket, so we poll them by block range
+pub async fn erc721_stream() {
+    dotenv::dotenv().ok();
+    run_erc721_stream(database::connect().await).await
+}
+
+pub async fn run_erc721_stream(db: DatabaseConnection) {
+    let evm_rpc_url = std::env::var("EVM_RPC_URL").expect("evm_rpc_url must be set");
+    let client = create_stream_client();
+    let evm_client = EvmClient::from(evm_rpc_url);
+
+    println!("🦀 polling erc721 logs");
 
     loop {
-        if let Err(error) = stream_handler(&db, &cosmos_client, &msg, pallet::tx_handler).await {
+        if let Err(error) = poll_erc721_logs(&db, &client, &evm_client).await {
             eprintln!("{}", error)
         }
+
+        tokio::time::sleep(EVM_POLL_INTERVAL).await;
     }
 }
 
-async fn stream_handler<'r, F, Fut>(
-    db: &'r DatabaseConnection,
-    cosmos_client: &'r CosmosClient,
-    msg_subcribe: &Message,
-    tx_handler: F,
-) -> Result<(), AppError>
-where
-    F: Fn(&'r DatabaseConnection, &'r CosmosClient, Transaction) -> Fut,
-    Fut: Future<Output = ()> + 'r,
-{
-    let wss_url = std::env::var("WSS_URL").expect("wss_url must be set");
-
-    let (ws_stream, _) = connect_async(wss_url).await?;
-
-    let (mut write, mut read) = ws_stream.split();
-
-    write.send(msg_subcribe.to_owned()).await?;
-
-    while let Some(message) = read.next().await {
-        if let Message::Text(message) = message? {
-            if message != INGORE_MESSAGE {
-                let message = serde_json::from_str::<Value>(&message)?;
-                let tx_result = Transaction::try_from_value(message)?;
-                tx_handler(db, cosmos_client, tx_result).await
-            } else {
-                // we skip first message, so this time is perfect to tell that stream is working
-                println!("🦀 listening stream")
-            }
+async fn poll_erc721_logs(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    evm_client: &dyn EvmChainClient,
+) -> Result<(), AppError> {
+    let latest_block = evm_client.get_block_number().await?;
+
+    // first run starts from the head, there is no backfill from genesis
+    let mut from_block = match ConfigRepository::find_by_key(db, ERC721_STREAM_BLOCK).await? {
+        Some(block) => {
+            block
+                .parse::<u64>()
+                .map_err(|e| AppError::Unexpected(e.to_string()))?
+                + 1
         }
+        None => latest_block,
+    };
+
+    while from_block <= latest_block {
+        let to_block = latest_block.min(from_block + EVM_BLOCK_RANGE - 1);
+
+        let logs = evm_client
+            .get_logs(from_block, to_block, vec![erc721::TRANSFER_TOPIC])
+            .await?;
+
+        for log in erc721::retrieve_erc721_logs(logs) {
+            erc721::log_handler(db, client, evm_client, log).await
+        }
+
+        ConfigRepository::upsert(db, ERC721_STREAM_BLOCK, to_block.to_string()).await?;
+
+        from_block = to_block + 1;
     }
 
     Ok(())
 }
 
-fn create_subcribe_message(query: Query) -> Message {
-    let msg = serde_json::json!({
-        "jsonrpc": "2.0",
-        "method": "subscribe",
-        "id": "0",
-        "params": {
-          "query": query.to_string()
-        }
-    });
+pub fn create_stream_client() -> StreamClient {
+    let rpc_url = std::env::var("RPC_URL").expect("rpc_url must be set");
+
+    let client = StreamClient::from(CosmosClient::from(
+        tendermint_rpc::HttpClient::new(rpc_url.as_str()).unwrap(),
+    ))
+    .with_cache(create_redis_pool());
+
+    match std::env::var("EVM_RPC_URL") {
+        Ok(evm_rpc_url) => client.with_evm(EvmClient::from(evm_rpc_url)),
+        Err(_) => client,
+    }
+}
 
-    let msg = Message::text(msg.to_string());
+fn cw721_query() -> Query {
+    Query::from(EventType::Tx)
+        .and_exists("wasm.action")
+        .and_exists("wasm._contract_address")
+        .and_exists("wasm.token_id")
+}
+
+fn pallet_query() -> Query {
+    Query::from(EventType::Tx).and_eq("execute._contract_address", PALLET_CONTRACT_ADDRESS)
+}
 
-    msg
+fn launchpad_query(launchpad_address: String) -> Query {
+    Query::from(EventType::Tx).and_eq("wasm._contract_address", launchpad_address)
 }
@@ -1,9 +1,12 @@
-use super::shared::{create_nft_or_update_owner_or_just_find, find_attribute, Attribute, Event};
+use super::shared::{
+    create_nft_or_update_owner_or_just_find, find_attribute, invalidate_collection_cache,
+    is_already_handled, Attribute, Event,
+};
 use super::Transaction;
 use crate::database::repository::tracing::{self as TracingRepository, CreateStreamTxParams};
 use crate::database::StreamContext;
 use crate::error::AppError;
-use crate::service::CosmosClient;
+use crate::service::StreamClient;
 use chrono::Utc;
 use sea_orm::DatabaseConnection;
 
@@ -11,7 +14,7 @@ static MINT_ACTION: &'static str = "mint";
 static TRANSFER_ACTION: &'static str = "transfer_nft";
 static SEND_ACTION: &'static str = "send_nft";
 
-pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Transaction) {
+pub async fn tx_handler(db: &DatabaseConnection, client: &StreamClient, tx: Transaction) {
     let Transaction { tx_hash, events } = tx;
 
     let events = retrieve_cw721_events(events);
@@ -24,6 +27,11 @@ pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Tran
             .map(|attribute| attribute.value.to_owned())
             .unwrap_or_default();
 
+        if is_already_handled(db, &tx_hash, StreamContext::Cwr721, &event).await {
+            println!("skip handled cw721 event {} {}", action, tx_hash);
+            continue;
+        }
+
         let result = if action == MINT_ACTION {
             hanlde_mint(db, client, &event).await
         } else if action == TRANSFER_ACTION {
@@ -58,6 +66,13 @@ pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Tran
         } else {
             println!("done handle cw721 event {} {}", action, tx_hash);
 
+            // mints and transfers change supply, owners and holders of the collection pages
+            if let Ok(collection_address) = find_attribute(&event, "_contract_address") {
+                invalidate_collection_cache(db, client, &collection_address)
+                    .await
+                    .unwrap_or_else(|e| eprintln!("error when invalidate cache \n>>{}", e));
+            }
+
             TracingRepository::create_stream_tx(
                 db,
                 CreateStreamTxParams {
@@ -78,7 +93,7 @@ pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Tran
 
 async fn hanlde_transfer(
     db: &DatabaseConnection,
-    client: &CosmosClient,
+    client: &StreamClient,
     event: &Event,
 ) -> Result<(), AppError> {
     let token_address = find_attribute(event, "_contract_address")?;
@@ -93,7 +108,7 @@ async fn hanlde_transfer(
 
 async fn hanlde_send(
     db: &DatabaseConnection,
-    client: &CosmosClient,
+    client: &StreamClient,
     event: &Event,
 ) -> Result<(), AppError> {
     let token_address = find_attribute(event, "_contract_address")?;
@@ -108,7 +123,7 @@ async fn hanlde_send(
 
 async fn hanlde_mint(
     db: &DatabaseConnection,
-    client: &CosmosClient,
+    client: &StreamClient,
     event: &Event,
 ) -> Result<(), AppError> {
     let token_address = find_attribute(event, "_contract_address")?;
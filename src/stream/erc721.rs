@@ -0,0 +1,205 @@
+use super::shared::invalidate_collection_cache;
+use crate::database::repository::{
+    collection::{self as CollectionRespository, CreateCollectionParams},
+    nft::{self as NftRepository, CreateNftParams},
+    tracing::{self as TracingRepository, CreateStreamTxParams},
+    wallet_link::{self as WalletLinkRepository},
+};
+use crate::database::StreamContext;
+use crate::error::AppError;
+use crate::service::{
+    topic_to_address, topic_to_token_id, EvmChainClient, EvmClientError, Log, PointerType,
+    StreamClient,
+};
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+
+// keccak256("Transfer(address,address,uint256)")
+pub static TRANSFER_TOPIC: &str =
+    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+static ZERO_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+static MINT_ACTION: &str = "mint";
+static TRANSFER_ACTION: &str = "transfer";
+
+pub async fn log_handler(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    evm_client: &dyn EvmChainClient,
+    log: Log,
+) {
+    let action = if topic_to_address(&log.topics[1]) == ZERO_ADDRESS {
+        MINT_ACTION
+    } else {
+        TRANSFER_ACTION
+    };
+
+    let result = handle_transfer(db, client, evm_client, &log).await;
+
+    TracingRepository::create_stream_tx(
+        db,
+        CreateStreamTxParams {
+            action: action.to_owned(),
+            context: StreamContext::Erc721,
+            date: Utc::now().into(),
+            event: serde_json::json!(log),
+            is_failure: result.is_err(),
+            tx_hash: log.transaction_hash.to_owned(),
+            message: result.as_ref().err().map(ToString::to_string),
+        },
+    )
+    .await
+    .unwrap_or_else(|e| eprintln!("error when create tracing tx \n>>{}", e));
+
+    match result {
+        Ok(token_address) => {
+            println!(
+                "done handle erc721 event {} {}",
+                action, log.transaction_hash
+            );
+
+            // new collections, mints and transfers change what the collection pages show
+            invalidate_collection_cache(db, client, &token_address)
+                .await
+                .unwrap_or_else(|e| eprintln!("error when invalidate cache \n>>{}", e));
+        }
+        Err(error) => eprintln!(
+            "error when handle erc721 event {} {} \n>>{}",
+            action, log.transaction_hash, error
+        ),
+    }
+}
+
+async fn handle_transfer(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    evm_client: &dyn EvmChainClient,
+    log: &Log,
+) -> Result<String, AppError> {
+    let evm_address = log.address.to_lowercase();
+    let recipient = topic_to_address(&log.topics[2]);
+    let token_id = topic_to_token_id(&log.topics[3])?;
+
+    let owner = to_sei_address(db, evm_client, recipient).await?;
+
+    let token_address =
+        create_erc721_collection_if_not_exist(db, client, evm_client, &evm_address).await?;
+
+    let nft = NftRepository::find_by_address_and_token_id(db, &token_address, &token_id).await?;
+
+    if nft.is_some() {
+        NftRepository::update_owner(db, &token_address, &token_id, Some(owner)).await?;
+
+        return Ok(token_address);
+    }
+
+    let token_uri = evm_client
+        .get_erc721_token_uri(&evm_address, &token_id)
+        .await?;
+
+    let metadata = client.nft_metadata.get_nft_metadata(&token_uri).await?;
+
+    NftRepository::create(
+        db,
+        CreateNftParams {
+            token_address: token_address.to_owned(),
+            token_id,
+            token_uri,
+            description: metadata.description,
+            image: metadata.image,
+            name: metadata.name,
+            owner_address: Some(owner),
+            traits: metadata.attributes,
+        },
+    )
+    .await?;
+
+    // the cw721 pointer when there is one, that is what the collection is cached under
+    Ok(token_address)
+}
+
+// erc721 collections are stored under their cw721 pointer when one exists,
+// so listings from cosmwasm marketplaces and evm transfers land on the same rows
+async fn create_erc721_collection_if_not_exist(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    evm_client: &dyn EvmChainClient,
+    evm_address: &str,
+) -> Result<String, AppError> {
+    if let Some(collection) = CollectionRespository::find_by_evm_address(db, evm_address).await? {
+        return Ok(collection.address);
+    }
+
+    let address = client
+        .chain
+        .get_pointer(PointerType::Erc721, evm_address)
+        .await?
+        .unwrap_or(evm_address.to_owned());
+
+    // indexed by the cw721 stream before, only the evm address is missing
+    if CollectionRespository::find_by_address(db, &address)
+        .await?
+        .is_some()
+    {
+        CollectionRespository::set_evm_address(db, &address, evm_address).await?;
+
+        return Ok(address);
+    }
+
+    let metadata = client
+        .collection_metadata
+        .get_collection_metadata(&address)
+        .await?;
+    let info = evm_client.get_erc721_contract_info(evm_address).await?;
+
+    // totalSupply is only part of the enumerable extension
+    let supply = evm_client
+        .get_erc721_total_supply(evm_address)
+        .await
+        .map(|supply| supply.count)
+        .unwrap_or_default();
+
+    CollectionRespository::create(
+        db,
+        CreateCollectionParams {
+            address: address.to_owned(),
+            symbol: info.symbol,
+            name: info.name,
+            metadata,
+            supply: supply as i32,
+            royalty: None,
+            royalty_address: None,
+            evm_address: Some(evm_address.to_owned()),
+        },
+    )
+    .await?;
+
+    Ok(address)
+}
+
+pub async fn to_sei_address(
+    db: &DatabaseConnection,
+    evm_client: &dyn EvmChainClient,
+    evm_address: String,
+) -> Result<String, AppError> {
+    if let Some(link) = WalletLinkRepository::find_by_evm_address(db, &evm_address).await? {
+        return Ok(link.sei_address);
+    }
+
+    match evm_client.get_sei_address(&evm_address).await {
+        Ok(sei_address) => {
+            WalletLinkRepository::create(db, sei_address.to_owned(), evm_address).await?;
+            Ok(sei_address)
+        }
+        // the wallet has not been associated on chain yet, keep the evm one
+        Err(EvmClientError::Rpc(_)) => Ok(evm_address),
+        Err(error) => Err(error.into()),
+    }
+}
+
+// erc20 Transfer shares the same signature but its amount is not indexed
+pub fn retrieve_erc721_logs(logs: Vec<Log>) -> Vec<Log> {
+    logs.into_iter()
+        .filter(|log| log.topics.len() == 4 && log.topics[0] == TRANSFER_TOPIC)
+        .collect()
+}
@@ -0,0 +1,254 @@
+use super::shared::{
+    create_nft_or_update_owner_or_just_find, find_attribute, invalidate_collection_cache,
+    is_already_handled, Attribute, Event,
+};
+use super::Transaction;
+use crate::database::repository::{
+    launchpad::{
+        self as LaunchpadRepository, CreateLaunchpadMintParams, CreateLaunchpadParams,
+        UpsertLaunchpadPhaseParams,
+    },
+    tracing::{self as TracingRepository, CreateStreamTxParams},
+};
+use crate::database::StreamContext;
+use crate::error::AppError;
+use crate::service::StreamClient;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    DatabaseConnection, TransactionTrait,
+};
+use std::str::FromStr;
+
+static REGISTER_COLLECTION_ACTION: &str = "register_collection";
+static UPDATE_MINT_GROUP_ACTION: &str = "update_mint_group";
+static MINT_ACTION: &str = "mint";
+
+static DEFAULT_DENOM: &str = "usei";
+
+pub async fn tx_handler(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    tx: Transaction,
+    launchpad_address: String,
+) {
+    let Transaction { tx_hash, events } = tx;
+
+    let events = retrieve_launchpad_events(events, &launchpad_address);
+
+    for event in events {
+        let action = event
+            .attributes
+            .iter()
+            .find(|Attribute { key, .. }| key == "action")
+            .map(|attribute| attribute.value.to_owned())
+            .unwrap_or_default();
+
+        if is_already_handled(db, &tx_hash, StreamContext::Launchpad, &event).await {
+            println!("skip handled launchpad event {} {}", action, tx_hash);
+            continue;
+        }
+
+        let result = if action == REGISTER_COLLECTION_ACTION {
+            handle_register_collection(db, &event).await
+        } else if action == UPDATE_MINT_GROUP_ACTION {
+            handle_update_mint_group(db, &event).await
+        } else if action == MINT_ACTION {
+            handle_mint(db, client, &event, &tx_hash).await
+        } else {
+            println!("unexpected action {} event {:#?}", action, event);
+            Ok(())
+        };
+
+        TracingRepository::create_stream_tx(
+            db,
+            CreateStreamTxParams {
+                action: action.to_owned(),
+                context: StreamContext::Launchpad,
+                date: Utc::now().into(),
+                event: serde_json::json!(event),
+                is_failure: result.is_err(),
+                tx_hash: tx_hash.to_owned(),
+                message: result.as_ref().err().map(ToString::to_string),
+            },
+        )
+        .await
+        .unwrap_or_else(|e| eprintln!("error when create tracing tx \n>>{}", e));
+
+        match result {
+            Ok(_) => {
+                println!("done handle launchpad event {} {}", action, tx_hash);
+
+                // registrations, mint groups and mints all show on the collection pages
+                if let Ok(collection_address) = find_attribute(&event, "collection") {
+                    invalidate_collection_cache(db, client, &collection_address)
+                        .await
+                        .unwrap_or_else(|e| eprintln!("error when invalidate cache \n>>{}", e));
+                }
+            }
+            Err(error) => eprintln!(
+                "error when handle launchpad event {} {} \n>>{}",
+                action, tx_hash, error
+            ),
+        }
+    }
+}
+
+async fn handle_register_collection(
+    db: &DatabaseConnection,
+    event: &Event,
+) -> Result<(), AppError> {
+    let contract_address = find_attribute(event, "_contract_address")?;
+    let collection_address = find_attribute(event, "collection")?;
+    let max_supply = find_optional_limit(event, "max_supply").map_err(AppError::Unexpected)?;
+
+    LaunchpadRepository::upsert(
+        db,
+        CreateLaunchpadParams {
+            collection_address,
+            contract_address,
+            max_supply,
+            date: Utc::now(),
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn handle_update_mint_group(db: &DatabaseConnection, event: &Event) -> Result<(), AppError> {
+    let contract_address = find_attribute(event, "_contract_address")?;
+    let collection_address = find_attribute(event, "collection")?;
+    let name = find_attribute(event, "group")?;
+    let start_date =
+        to_date(&find_attribute(event, "start_time")?).map_err(AppError::Unexpected)?;
+    let price = to_decimal(&find_attribute(event, "unit_price")?).map_err(AppError::Unexpected)?;
+    let max_per_wallet = find_optional_limit(event, "max_tokens").map_err(AppError::Unexpected)?;
+
+    // a zero end time means the group stays open until sold out
+    let end_date = match find_attribute(event, "end_time") {
+        Ok(end_time) if end_time != "0" => Some(to_date(&end_time).map_err(AppError::Unexpected)?),
+        _ => None,
+    };
+
+    let denom = find_attribute(event, "denom").unwrap_or(DEFAULT_DENOM.to_owned());
+
+    LaunchpadRepository::create_if_not_exist(
+        db,
+        CreateLaunchpadParams {
+            collection_address: collection_address.to_owned(),
+            contract_address,
+            max_supply: None,
+            date: Utc::now(),
+        },
+    )
+    .await?;
+
+    LaunchpadRepository::upsert_phase(
+        db,
+        UpsertLaunchpadPhaseParams {
+            collection_address,
+            name,
+            start_date,
+            end_date,
+            price,
+            denom,
+            max_per_wallet,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn handle_mint(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    event: &Event,
+    tx_hash: &str,
+) -> Result<(), AppError> {
+    let contract_address = find_attribute(event, "_contract_address")?;
+    let collection_address = find_attribute(event, "collection")?;
+    let phase = find_attribute(event, "group")?;
+    let token_id = find_attribute(event, "token_id")?;
+    let recipient = find_attribute(event, "recipient")?;
+    let price = to_decimal(&find_attribute(event, "price")?).map_err(AppError::Unexpected)?;
+    let denom = find_attribute(event, "denom").unwrap_or(DEFAULT_DENOM.to_owned());
+
+    let nft_id = create_nft_or_update_owner_or_just_find(
+        db,
+        client,
+        collection_address.to_owned(),
+        token_id.to_owned(),
+        Some(recipient.to_owned()),
+    )
+    .await?;
+
+    LaunchpadRepository::create_if_not_exist(
+        db,
+        CreateLaunchpadParams {
+            collection_address: collection_address.to_owned(),
+            contract_address,
+            max_supply: None,
+            date: Utc::now(),
+        },
+    )
+    .await?;
+
+    let tx = db.begin().await?;
+
+    LaunchpadRepository::create_mint(
+        &tx,
+        CreateLaunchpadMintParams {
+            tx_hash: tx_hash.to_owned(),
+            date: Utc::now(),
+            collection_address,
+            phase,
+            wallet_address: recipient,
+            token_id,
+            nft_id,
+            price,
+            denom,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+// limits are optional on the contract, zero or missing means unlimited
+fn find_optional_limit(event: &Event, key: &str) -> Result<Option<i32>, String> {
+    match find_attribute(event, key) {
+        Ok(value) => {
+            let limit = value.parse::<i32>().map_err(|e| e.to_string())?;
+
+            Ok(Some(limit).filter(|limit| *limit > 0))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+fn to_date(seconds: &str) -> Result<DateTimeUtc, String> {
+    let seconds = seconds.parse::<i64>().map_err(|e| e.to_string())?;
+
+    DateTime::from_timestamp(seconds, 0).ok_or(format!("invalid timestamp {}", seconds))
+}
+
+fn to_decimal(amount: &str) -> Result<Decimal, String> {
+    Decimal::from_str(amount).map_err(|e| e.to_string())
+}
+
+// cw721 emits its own `mint` wasm event in the same tx, only keep the ones from the launchpad contract
+fn retrieve_launchpad_events(events: Vec<Event>, launchpad_address: &str) -> Vec<Event> {
+    events
+        .into_iter()
+        .filter(|event| {
+            event.r#type == "wasm"
+                && event.attributes.iter().any(|Attribute { key, value }| {
+                    key == "_contract_address" && *value == launchpad_address
+                })
+        })
+        .collect()
+}
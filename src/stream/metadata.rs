@@ -0,0 +1,316 @@
+use super::shared::{find_cw2981_royalty, invalidate_collection_cache, Royalty};
+use crate::{
+    database::repository::{
+        collection::{self as CollectionRespository, UpdateCollectionMetadataParams},
+        metadata_refresh::{self as MetadataRefreshRepository, CreateMetadataRefreshParams},
+        nft::{self as NftRepository, UpdateNftMetadataParams},
+    },
+    error::AppError,
+    service::{CollectionMetadata, MetadataError, NftTrait, StreamClient},
+};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+
+// shared by the refresh endpoint and the unrevealed job, a refresh fetches every token uri
+pub static COLLECTION_REFRESH_COOLDOWN_SECONDS: u64 = 900;
+
+pub fn collection_refresh_key(address: &str) -> String {
+    format!("metadata_refresh:{}", address)
+}
+
+// collection level changes are only recorded, the summary counts the nfts
+pub struct CollectionRefresh {
+    pub refreshed_nfts: usize,
+    pub changed_nfts: usize,
+    pub failed_nfts: usize,
+}
+
+// re-fetches the token uri and its metadata, returns what changed when anything did
+pub async fn refresh_nft(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    collection_address: &str,
+    token_id: &str,
+) -> Result<Option<Value>, AppError> {
+    let changes = refresh_nft_metadata(db, client, collection_address, token_id).await?;
+
+    if changes.is_some() {
+        invalidate_collection_cache(db, client, collection_address)
+            .await
+            .unwrap_or_else(|e| eprintln!("error when invalidate cache \n>>{}", e));
+    }
+
+    Ok(changes)
+}
+
+// one failing token uri should not stop the others, failures are only counted
+pub async fn refresh_collection(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    address: &str,
+) -> Result<CollectionRefresh, AppError> {
+    let token_ids = NftRepository::find_token_ids(db, address).await?;
+
+    refresh_collection_metadata(db, client, address, token_ids.first()).await?;
+
+    let mut changed_nfts = 0;
+    let mut failed_nfts = 0;
+
+    for token_id in &token_ids {
+        match refresh_nft_metadata(db, client, address, token_id).await {
+            Ok(Some(_)) => changed_nfts += 1,
+            Ok(None) => {}
+            Err(error) => {
+                failed_nfts += 1;
+                eprintln!(
+                    "error when refresh metadata of {} {} \n>>{}",
+                    address, token_id, error
+                );
+            }
+        }
+    }
+
+    invalidate_collection_cache(db, client, address)
+        .await
+        .unwrap_or_else(|e| eprintln!("error when invalidate cache \n>>{}", e));
+
+    Ok(CollectionRefresh {
+        refreshed_nfts: token_ids.len(),
+        changed_nfts,
+        failed_nfts,
+    })
+}
+
+async fn refresh_nft_metadata(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    collection_address: &str,
+    token_id: &str,
+) -> Result<Option<Value>, AppError> {
+    let nft = NftRepository::find_by_address_and_token_id(db, collection_address, token_id)
+        .await?
+        .ok_or(AppError::NotFound(format!(
+            "nft {} of {} not found",
+            token_id, collection_address
+        )))?;
+
+    // evm only collections have no cw721 contract to ask, their erc721 contract is asked instead
+    let token_uri = if is_evm_address(collection_address) {
+        client
+            .evm
+            .as_deref()
+            .ok_or(AppError::Unexpected(
+                "evm_rpc_url is not set, erc721 token uris can not be refreshed".to_owned(),
+            ))?
+            .get_erc721_token_uri(collection_address, token_id)
+            .await?
+    } else {
+        client
+            .chain
+            .get_nft_info(collection_address, token_id)
+            .await?
+            .token_uri
+    };
+
+    let metadata = client.nft_metadata.get_nft_metadata(&token_uri).await?;
+
+    let old_traits: BTreeSet<NftTrait> = NftRepository::find_traits(db, nft.id)
+        .await?
+        .into_iter()
+        .collect();
+    let new_traits: BTreeSet<NftTrait> = metadata
+        .attributes
+        .unwrap_or_default()
+        .into_iter()
+        .map(NftTrait::from)
+        .collect();
+
+    let mut changes = Map::new();
+
+    diff(&mut changes, "token_uri", &nft.token_uri, &token_uri);
+    diff(&mut changes, "name", &nft.name, &metadata.name);
+    diff(&mut changes, "image", &nft.image, &metadata.image);
+    diff(
+        &mut changes,
+        "description",
+        &nft.description,
+        &metadata.description,
+    );
+
+    let traits_changed = old_traits != new_traits;
+
+    if traits_changed {
+        changes.insert(
+            "traits".to_owned(),
+            json!({
+                "added": new_traits.difference(&old_traits).collect::<Vec<_>>(),
+                "removed": old_traits.difference(&new_traits).collect::<Vec<_>>(),
+            }),
+        );
+    }
+
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    NftRepository::update_metadata(
+        db,
+        UpdateNftMetadataParams {
+            nft_id: nft.id,
+            token_address: collection_address.to_owned(),
+            token_uri,
+            name: metadata.name,
+            image: metadata.image,
+            description: metadata.description,
+            traits: traits_changed.then(|| new_traits.into_iter().collect()),
+        },
+    )
+    .await?;
+
+    let changes = Value::Object(changes);
+
+    MetadataRefreshRepository::create(
+        db,
+        CreateMetadataRefreshParams {
+            collection_address: collection_address.to_owned(),
+            token_id: Some(token_id.to_owned()),
+            changes: changes.to_owned(),
+        },
+    )
+    .await?;
+
+    Ok(Some(changes))
+}
+
+async fn refresh_collection_metadata(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    address: &str,
+    token_id: Option<&String>,
+) -> Result<Option<Value>, AppError> {
+    let collection = CollectionRespository::find_by_address(db, address)
+        .await?
+        .ok_or(AppError::NotFound(format!(
+            "collection {} not found",
+            address
+        )))?;
+
+    // pallet does not curate every collection, those keep what they already have
+    let metadata = match client
+        .collection_metadata
+        .get_collection_metadata(address)
+        .await
+    {
+        Ok(metadata) => metadata,
+        Err(MetadataError::NotFound(_)) => CollectionMetadata {
+            pfp: collection.image.to_owned(),
+            slug: collection.slug.to_owned(),
+            description: collection.description.to_owned(),
+            banner: collection.banner.to_owned(),
+            socials: collection.socials.to_owned(),
+        },
+        Err(error) => return Err(error.into()),
+    };
+
+    let (name, symbol, supply) = if is_evm_address(address) {
+        (
+            collection.name.to_owned(),
+            collection.symbol.to_owned(),
+            collection.supply,
+        )
+    } else {
+        let info = client.chain.get_cw721_contract_info(address).await?;
+        let supply = client.chain.get_cw721_contract_supply(address).await?;
+
+        (info.name, info.symbol, supply.count as i32)
+    };
+
+    // cw2981 royalties are asked per token, the first one stands for the collection
+    let cw2981_royalty = match token_id {
+        Some(token_id) if !is_evm_address(address) => {
+            find_cw2981_royalty(client, address, token_id).await
+        }
+        _ => None,
+    };
+    let royalty = cw2981_royalty.unwrap_or(Royalty {
+        percentage: collection.royalty,
+        address: collection.royalty_address.to_owned(),
+    });
+
+    let mut changes = Map::new();
+
+    diff(&mut changes, "name", &collection.name, &name);
+    diff(&mut changes, "symbol", &collection.symbol, &symbol);
+    diff(&mut changes, "supply", &collection.supply, &supply);
+    diff(
+        &mut changes,
+        "royalty",
+        &collection.royalty,
+        &royalty.percentage,
+    );
+    diff(
+        &mut changes,
+        "royalty_address",
+        &collection.royalty_address,
+        &royalty.address,
+    );
+    diff(&mut changes, "image", &collection.image, &metadata.pfp);
+    diff(&mut changes, "banner", &collection.banner, &metadata.banner);
+    diff(
+        &mut changes,
+        "description",
+        &collection.description,
+        &metadata.description,
+    );
+    diff(
+        &mut changes,
+        "socials",
+        &collection.socials,
+        &metadata.socials,
+    );
+
+    // always written, metadata_refreshed_at is how the unrevealed job paces its rechecks
+    CollectionRespository::update_metadata(
+        db,
+        UpdateCollectionMetadataParams {
+            address: address.to_owned(),
+            name,
+            symbol,
+            supply,
+            royalty: royalty.percentage,
+            royalty_address: royalty.address,
+            metadata,
+        },
+    )
+    .await?;
+
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let changes = Value::Object(changes);
+
+    MetadataRefreshRepository::create(
+        db,
+        CreateMetadataRefreshParams {
+            collection_address: address.to_owned(),
+            token_id: None,
+            changes: changes.to_owned(),
+        },
+    )
+    .await?;
+
+    Ok(Some(changes))
+}
+
+fn diff<T: PartialEq + Serialize>(changes: &mut Map<String, Value>, field: &str, from: &T, to: &T) {
+    if from != to {
+        changes.insert(field.to_owned(), json!({ "from": from, "to": to }));
+    }
+}
+
+fn is_evm_address(address: &str) -> bool {
+    address.starts_with("0x")
+}
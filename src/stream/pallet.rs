@@ -1,10 +1,12 @@
 use super::shared::{
     create_activity_transaction_and_point_on_sale, create_nft_or_update_owner_or_just_find,
-    find_attribute, to_utf8, CreateActivityTransactionAndPointOnSaleParams, Event, Transaction,
+    find_attribute, invalidate_collection_cache, is_already_handled,
+    CreateActivityTransactionAndPointOnSaleParams, Event, Transaction,
 };
 use crate::{
     database::{
         repository::{
+            collection as CollectionRepository, collection_stats as CollectionStatsRepository,
             nft::{self as NftRepository, CreatePalletListingParams},
             nft_activity::{self as NftActivityRepository, CreateNftActivityParams},
             tracing::{self as TracingRepository, CreateStreamTxParams},
@@ -12,18 +14,17 @@ use crate::{
         Marketplace, NftActivityKind, StreamContext,
     },
     error::AppError,
-    service::{CosmosClient, PalletListing},
+    service::{PalletListing, StreamClient},
 };
 use chrono::{DateTime, Utc};
 use sea_orm::{prelude::Decimal, DatabaseConnection, TransactionTrait};
 use std::str::FromStr;
-use tendermint_rpc::endpoint::tx;
 
 static CREATE_AUCTION_ACTION: &'static str = "wasm-create_auction";
 static BUY_NOW_AUCTION: &'static str = "wasm-buy_now";
 static CANCEL_AUCTION: &'static str = "wasm-cancel_auction";
 
-pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Transaction) {
+pub async fn tx_handler(db: &DatabaseConnection, client: &StreamClient, tx: Transaction) {
     let Transaction { tx_hash, events } = tx;
 
     let events = retrieve_pallet_events(events);
@@ -31,6 +32,11 @@ pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Tran
     for event in events {
         let action = &event.r#type;
 
+        if is_already_handled(db, &tx_hash, StreamContext::Pallet, &event).await {
+            println!("skip handled pallet event {} {}", action, tx_hash);
+            continue;
+        }
+
         let result = if action == CREATE_AUCTION_ACTION {
             handle_create_auction(db, client, &event, &tx_hash).await
         } else if action == BUY_NOW_AUCTION {
@@ -78,6 +84,13 @@ pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Tran
             .await
             .unwrap_or_else(|e| eprintln!("error when create tracing tx \n>>{}", e));
 
+            // every pallet action lists, sells or delists, all of them change what the collection pages show
+            if let Ok(collection_address) = find_attribute(&event, "collection_address") {
+                invalidate_collection_cache(db, client, &collection_address)
+                    .await
+                    .unwrap_or_else(|e| eprintln!("error when invalidate cache \n>>{}", e));
+            }
+
             println!("done handle pallet event {} {}", action, tx_hash);
         }
     }
@@ -85,7 +98,7 @@ pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Tran
 
 async fn handle_create_auction(
     db: &DatabaseConnection,
-    client: &CosmosClient,
+    client: &StreamClient,
     event: &Event,
     tx_hash: &String,
 ) -> Result<(), AppError> {
@@ -101,7 +114,10 @@ async fn handle_create_auction(
     )
     .await?;
 
-    let pallet_listing = client.get_pallet_listing(&token_address, &token_id).await?;
+    let pallet_listing = client
+        .chain
+        .get_pallet_listing(&token_address, &token_id)
+        .await?;
 
     let PalletListing { auction, owner } = pallet_listing;
 
@@ -130,13 +146,15 @@ async fn handle_create_auction(
             denom: "usei".to_string(),
             nft_id,
             tx_hash: tx_hash.to_owned(),
-            collection_address: token_address,
+            collection_address: token_address.to_owned(),
             expiration_time: Some(auction.expiration_time as i32),
             seller: owner.to_owned(),
         },
     )
     .await?;
 
+    CollectionStatsRepository::refresh_listing_stats(&tx, &token_address).await?;
+
     NftActivityRepository::create(
         &tx,
         CreateNftActivityParams {
@@ -161,7 +179,7 @@ async fn handle_create_auction(
 
 async fn handle_buy_now(
     db: &DatabaseConnection,
-    client: &CosmosClient,
+    client: &StreamClient,
     event: &Event,
     tx_hash: &String,
 ) -> Result<(), AppError> {
@@ -183,17 +201,40 @@ async fn handle_buy_now(
         return Ok(());
     };
 
-    let tx = client.get_tx(&tx_hash).await?;
+    // sei returns the attributes of a queried tx base64 encoded like the streamed ones
+    let tx = Transaction::from(client.chain.get_tx(&tx_hash).await?);
+    let message_events = find_message_events(&tx, event);
 
-    let buyer = find_buyer_address_from_tx(&tx).ok_or(AppError::Unexpected(format!(
+    let buyer = find_buyer_address(message_events).ok_or(AppError::Unexpected(format!(
         "can not get buyer from tx {} in buy now event",
         tx_hash,
     )))?;
 
+    let collection = CollectionRepository::find_by_address(db, &token_address).await?;
+    let royalty_address = collection
+        .as_ref()
+        .and_then(|collection| collection.royalty_address.to_owned());
+
+    let royalty = match (
+        &royalty_address,
+        collection.and_then(|collection| collection.royalty),
+    ) {
+        (Some(royalty_address), _) => {
+            find_royalty_paid(message_events, royalty_address, &db_listing.seller_address)
+        }
+        // nowhere to follow the transfers to, the configured share of the price is what was owed
+        (None, Some(percentage)) => {
+            (db_listing.price * percentage / Decimal::ONE_HUNDRED).round_dp(2)
+        }
+        (None, None) => Decimal::ZERO,
+    };
+
     let tx = db.begin().await?;
 
     NftRepository::delete_listing_if_exist(&tx, nft_id).await?;
 
+    CollectionStatsRepository::refresh_listing_stats(&tx, &token_address).await?;
+
     create_activity_transaction_and_point_on_sale(
         &tx,
         CreateActivityTransactionAndPointOnSaleParams {
@@ -204,9 +245,12 @@ async fn handle_buy_now(
             marketplace: Marketplace::Pallet,
             metadata: serde_json::json!({}),
             nft_id,
-            price: db_listing.price.to_string(),
+            // numeric(90, 2) comes back as "5000000.00", points are parsed from an integer
+            price: db_listing.price.normalize().to_string(),
             seller: db_listing.seller_address,
             tx_hash: tx_hash.to_owned(),
+            royalty,
+            royalty_address,
         },
     )
     .await?;
@@ -218,15 +262,21 @@ async fn handle_buy_now(
 
 async fn handle_cancel_auction(
     db: &DatabaseConnection,
-    client: &CosmosClient,
+    client: &StreamClient,
     event: &Event,
     tx_hash: &String,
 ) -> Result<(), AppError> {
     let token_address = find_attribute(event, "collection_address")?;
     let token_id = find_attribute(event, "token_id")?;
 
-    let nft_id =
-        create_nft_or_update_owner_or_just_find(db, client, token_address, token_id, None).await?;
+    let nft_id = create_nft_or_update_owner_or_just_find(
+        db,
+        client,
+        token_address.to_owned(),
+        token_id,
+        None,
+    )
+    .await?;
 
     let db_listing = NftRepository::find_listing_by_nft_id(db, nft_id).await?;
 
@@ -238,6 +288,8 @@ async fn handle_cancel_auction(
 
     NftRepository::delete_listing_if_exist(&tx, nft_id).await?;
 
+    CollectionStatsRepository::refresh_listing_stats(&tx, &token_address).await?;
+
     NftActivityRepository::create(
         &tx,
         CreateNftActivityParams {
@@ -260,23 +312,77 @@ async fn handle_cancel_auction(
     Ok(())
 }
 
-fn find_buyer_address_from_tx(tx: &tx::Response) -> Option<String> {
-    tx.tx_result
-        .events
+// a tx can buy several nfts, one message each, so the buyer and the royalty transfers are only
+// looked for among the events of the message that emitted the buy
+fn find_message_events<'a>(tx: &'a Transaction, event: &Event) -> &'a [Event] {
+    let events = tx.events.as_slice();
+
+    let Some(position) = events.iter().position(|e| e == event) else {
+        return &[];
+    };
+
+    // cosmos sdk 0.50 tags every event with the index of the message that emitted it
+    if let Ok(msg_index) = find_attribute(event, "msg_index") {
+        // the fee and signature events of the tx itself carry no index
+        let other_message =
+            |e: &Event| !find_attribute(e, "msg_index").is_ok_and(|index| index == msg_index);
+
+        let start = events[..position]
+            .iter()
+            .rposition(other_message)
+            .map_or(0, |index| index + 1);
+        let end = events[position..]
+            .iter()
+            .position(other_message)
+            .map_or(events.len(), |index| position + index);
+
+        return &events[start..end];
+    }
+
+    // before that every message starts with a message event naming its action
+    let message_start = |e: &Event| e.r#type == "message" && find_attribute(e, "action").is_ok();
+
+    let start = events[..position]
+        .iter()
+        .rposition(message_start)
+        .unwrap_or(0);
+    let end = events[position..]
+        .iter()
+        .position(message_start)
+        .map_or(events.len(), |index| position + index);
+
+    &events[start..end]
+}
+
+fn find_buyer_address(events: &[Event]) -> Option<String> {
+    events
         .iter()
-        .find_map(|e| {
-            if e.kind != "wasm" {
-                None
-            } else {
-                e.attributes.iter().find(|attribute| {
-                    attribute
-                        .key_str()
-                        .is_ok_and(|key| to_utf8(key) == "recipent")
-                })
-            }
+        .filter(|e| e.r#type == "wasm")
+        .find_map(|e| find_attribute(e, "recipent").ok())
+}
+
+// whatever the sale sent to the royalty address through bank transfers, a creator selling
+// its own nft receives the proceeds there which are no royalty
+fn find_royalty_paid(events: &[Event], royalty_address: &str, seller: &str) -> Decimal {
+    if royalty_address == seller {
+        return Decimal::ZERO;
+    }
+
+    events
+        .iter()
+        .filter(|e| e.r#type == "transfer")
+        .filter(|e| {
+            find_attribute(e, "recipient").is_ok_and(|recipient| recipient == royalty_address)
+        })
+        .filter_map(|e| find_attribute(e, "amount").ok())
+        .flat_map(|amount| {
+            amount
+                .split(',')
+                .filter_map(|coin| coin.strip_suffix("usei"))
+                .filter_map(|amount| Decimal::from_str(amount).ok())
+                .collect::<Vec<_>>()
         })
-        .and_then(|attribute| attribute.value_str().ok())
-        .map(str::to_owned)
+        .sum()
 }
 
 fn retrieve_pallet_events(events: Vec<Event>) -> Vec<Event> {
@@ -287,3 +393,353 @@ fn retrieve_pallet_events(events: Vec<Event>) -> Vec<Event> {
         })
         .collect()
 }
+
+// the fixtures are shared with the recovery tests of the subscriptions
+#[cfg(all(test, feature = "fake"))]
+pub(super) mod tests {
+    use super::*;
+    use crate::{
+        database::{self, ListingNft},
+        service::{
+            fake::{FakeChainClient, FakeCollectionMetadata, FakeNftMetadata},
+            CollectionMetadata, ContractInfo, NftInfo, NftMetadata, PalletAuction, Price, Supply,
+        },
+        stream::shared::Attribute,
+    };
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use sea_orm::{ConnectionTrait, Statement};
+    use tendermint::{abci, Hash};
+    use tendermint_rpc::endpoint::tx;
+
+    pub(crate) static COLLECTION: &str = "sei1collection";
+    pub(crate) static TOKEN_ID: &str = "7";
+    static OTHER_TOKEN_ID: &str = "8";
+    static SELLER: &str = "sei1seller";
+    pub(crate) static BUYER: &str = "sei1buyer";
+    static OTHER_BUYER: &str = "sei1otherbuyer";
+    static ROYALTY_ADDRESS: &str = "sei1creator";
+    static CREATE_AUCTION_TX: &str =
+        "1111111111111111111111111111111111111111111111111111111111111111";
+    pub(crate) static BUY_NOW_TX: &str =
+        "2222222222222222222222222222222222222222222222222222222222222222";
+
+    pub(crate) fn stream_client(chain: FakeChainClient) -> StreamClient {
+        let mut chain = chain.with_contract(
+            COLLECTION,
+            ContractInfo {
+                name: "Collection".to_owned(),
+                symbol: "COL".to_owned(),
+            },
+            Supply { count: 10 },
+        );
+        let mut nft_metadata = FakeNftMetadata::default();
+
+        for token_id in [TOKEN_ID, OTHER_TOKEN_ID] {
+            let token_uri = format!("ipfs://{}", token_id);
+
+            chain = chain.with_nft(
+                COLLECTION,
+                token_id,
+                NftInfo {
+                    token_uri: token_uri.to_owned(),
+                    extension: None,
+                },
+            );
+            nft_metadata = nft_metadata.with_metadata(
+                &token_uri,
+                NftMetadata {
+                    name: Some(format!("Token {}", token_id)),
+                    description: None,
+                    image: None,
+                    attributes: None,
+                },
+            );
+        }
+
+        let collection_metadata = FakeCollectionMetadata::default().with_metadata(
+            COLLECTION,
+            CollectionMetadata {
+                pfp: None,
+                slug: None,
+                description: None,
+                banner: None,
+                socials: None,
+            },
+        );
+
+        StreamClient::new(chain, nft_metadata, collection_metadata)
+    }
+
+    fn pallet_event(action: &str, token_id: &str) -> Event {
+        Event {
+            r#type: action.to_owned(),
+            attributes: vec![
+                Attribute {
+                    key: "collection_address".to_owned(),
+                    value: COLLECTION.to_owned(),
+                },
+                Attribute {
+                    key: "token_id".to_owned(),
+                    value: token_id.to_owned(),
+                },
+            ],
+        }
+    }
+
+    // sei returns event attributes base64 encoded
+    pub(crate) fn chain_event(kind: &str, attributes: &[(&str, &str)]) -> abci::Event {
+        abci::Event::new(
+            kind,
+            attributes
+                .iter()
+                .map(|(key, value)| (BASE64_STANDARD.encode(key), BASE64_STANDARD.encode(value))),
+        )
+    }
+
+    fn chain_tx(tx_hash: &str, events: Vec<abci::Event>) -> tx::Response {
+        tx::Response {
+            hash: Hash::from_str(tx_hash).unwrap(),
+            height: 1u32.into(),
+            index: 0,
+            tx_result: abci::types::ExecTxResult {
+                events,
+                ..Default::default()
+            },
+            tx: vec![],
+            proof: None,
+        }
+    }
+
+    // the events of one buy now message, transfers are (recipient, amount)
+    fn buy_now_message(
+        token_id: &str,
+        buyer: &str,
+        transfers: &[(&str, &str)],
+    ) -> Vec<abci::Event> {
+        let mut events = vec![
+            chain_event(
+                "message",
+                &[("action", "/cosmwasm.wasm.v1.MsgExecuteContract")],
+            ),
+            chain_event("wasm", &[("action", "buy_now"), ("recipent", buyer)]),
+            chain_event(
+                BUY_NOW_AUCTION,
+                &[("collection_address", COLLECTION), ("token_id", token_id)],
+            ),
+        ];
+
+        for (recipient, amount) in transfers {
+            events.push(chain_event(
+                "transfer",
+                &[("recipient", recipient), ("amount", amount)],
+            ));
+        }
+
+        events
+    }
+
+    // handled the way the stream sees it, the buy now events come from the same tx the handler queries
+    async fn buy_now(
+        db: &DatabaseConnection,
+        client: &StreamClient,
+        chain: &FakeChainClient,
+        messages: Vec<Vec<abci::Event>>,
+    ) {
+        let tx = chain_tx(BUY_NOW_TX, messages.into_iter().flatten().collect());
+
+        chain.set_tx(BUY_NOW_TX, tx.clone());
+
+        tx_handler(db, client, Transaction::from(tx)).await;
+    }
+
+    pub(crate) async fn count(db: &DatabaseConnection, sql: &str) -> i64 {
+        db.query_one(Statement::from_string(db.get_database_backend(), sql))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get_by_index::<i64>(0)
+            .unwrap()
+    }
+
+    pub(crate) async fn create_auction(
+        db: &DatabaseConnection,
+        client: &StreamClient,
+        chain: &FakeChainClient,
+        token_id: &str,
+        price: &str,
+    ) -> ListingNft {
+        chain.set_pallet_listing(
+            COLLECTION,
+            token_id,
+            PalletListing {
+                owner: SELLER.to_owned(),
+                auction: Some(PalletAuction {
+                    created_at: 1_700_000_000,
+                    expiration_time: 1_800_000_000,
+                    prices: [Price {
+                        amount: price.to_owned(),
+                        denom: "usei".to_owned(),
+                    }],
+                }),
+            },
+        );
+
+        tx_handler(
+            db,
+            client,
+            Transaction {
+                tx_hash: CREATE_AUCTION_TX.to_owned(),
+                events: vec![pallet_event(CREATE_AUCTION_ACTION, token_id)],
+            },
+        )
+        .await;
+
+        let nft = NftRepository::find_by_address_and_token_id(db, COLLECTION, token_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        NftRepository::find_listing_by_nft_id(db, nft.id)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_auction_then_buy_now() {
+        let Some(db) = database::test::connect().await else {
+            return;
+        };
+
+        let chain = FakeChainClient::default();
+        let client = stream_client(chain.clone());
+
+        let listing = create_auction(&db, &client, &chain, TOKEN_ID, "5000000").await;
+
+        assert_eq!(listing.seller_address, SELLER);
+        assert_eq!(listing.price, Decimal::from(5_000_000));
+        assert_eq!(
+            count(&db, r#"SELECT "listed"::bigint FROM "collection_stats""#).await,
+            1
+        );
+
+        buy_now(
+            &db,
+            &client,
+            &chain,
+            vec![buy_now_message(TOKEN_ID, BUYER, &[])],
+        )
+        .await;
+
+        assert!(NftRepository::find_listing_by_nft_id(&db, listing.nft_id)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            count(&db, r#"SELECT "listed"::bigint FROM "collection_stats""#).await,
+            0
+        );
+        assert_eq!(
+            count(
+                &db,
+                r#"SELECT count(*) FROM "transaction" WHERE "volume" = 5000000"#
+            )
+            .await,
+            1
+        );
+        assert_eq!(
+            count(
+                &db,
+                r#"SELECT count(*) FROM "stream_tx" WHERE "is_failure""#
+            )
+            .await,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn buy_now_records_the_royalty_paid() {
+        let Some(db) = database::test::connect().await else {
+            return;
+        };
+
+        let chain = FakeChainClient::default().with_royalty(COLLECTION, ROYALTY_ADDRESS, 5);
+        let client = stream_client(chain.clone());
+
+        create_auction(&db, &client, &chain, TOKEN_ID, "5000000").await;
+
+        buy_now(
+            &db,
+            &client,
+            &chain,
+            vec![buy_now_message(
+                TOKEN_ID,
+                BUYER,
+                &[(SELLER, "4750000usei"), (ROYALTY_ADDRESS, "250000usei")],
+            )],
+        )
+        .await;
+
+        assert_eq!(
+            count(
+                &db,
+                &format!(
+                    r#"SELECT count(*) FROM "transaction" WHERE "buyer_address" = '{}' AND "royalty" = 250000 AND "royalty_address" = '{}'"#,
+                    BUYER, ROYALTY_ADDRESS
+                )
+            )
+            .await,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn buy_now_reads_each_sale_of_a_tx_from_its_own_message() {
+        let Some(db) = database::test::connect().await else {
+            return;
+        };
+
+        let chain = FakeChainClient::default().with_royalty(COLLECTION, ROYALTY_ADDRESS, 5);
+        let client = stream_client(chain.clone());
+
+        create_auction(&db, &client, &chain, TOKEN_ID, "5000000").await;
+        create_auction(&db, &client, &chain, OTHER_TOKEN_ID, "2000000").await;
+
+        buy_now(
+            &db,
+            &client,
+            &chain,
+            vec![
+                buy_now_message(
+                    TOKEN_ID,
+                    BUYER,
+                    &[(SELLER, "4750000usei"), (ROYALTY_ADDRESS, "250000usei")],
+                ),
+                buy_now_message(
+                    OTHER_TOKEN_ID,
+                    OTHER_BUYER,
+                    &[(SELLER, "1900000usei"), (ROYALTY_ADDRESS, "100000usei")],
+                ),
+            ],
+        )
+        .await;
+
+        for (buyer, volume, royalty) in [
+            (BUYER, 5_000_000, 250_000),
+            (OTHER_BUYER, 2_000_000, 100_000),
+        ] {
+            assert_eq!(
+                count(
+                    &db,
+                    &format!(
+                        r#"SELECT count(*) FROM "transaction" WHERE "buyer_address" = '{}' AND "volume" = {} AND "royalty" = {}"#,
+                        buyer, volume, royalty
+                    )
+                )
+                .await,
+                1
+            );
+        }
+        assert_eq!(count(&db, r#"SELECT count(*) FROM "transaction""#).await, 2);
+    }
+}
@@ -1,16 +1,19 @@
 use crate::{
+    cache::{self, collection_tag, COLLECTIONS_TAG},
     database::{
         repository::{
             collection::{self as CollectionRespository, CreateCollectionParams},
+            collection_stats as CollectionStatsRepository,
             nft::{self as NftRepository, CreateNftParams},
             nft_activity::{self as NftActivityRepository, CreateNftActivityParams},
+            tracing as TracingRepository,
             transaction::{self as TransactionRepository, CreateTransactionParams},
             user_point::{self as UserPointRepository, CreateUserPointParams},
         },
-        LoyaltyPointKind, Marketplace, NftActivityKind,
+        LoyaltyPointKind, Marketplace, NftActivityKind, StreamContext,
     },
     error::AppError,
-    service::{get_collection_metadata, get_nft_metadata, CosmosClient},
+    service::{PointerType, StreamClient},
 };
 use base64::{prelude::BASE64_STANDARD, Engine};
 use sea_orm::{
@@ -18,6 +21,7 @@ use sea_orm::{
     DatabaseConnection, DatabaseTransaction,
 };
 use std::str::FromStr;
+use tendermint_rpc::endpoint::tx;
 
 #[derive(Debug)]
 pub struct Transaction {
@@ -25,23 +29,32 @@ pub struct Transaction {
     pub events: Vec<Event>,
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
 pub struct Event {
     pub r#type: String,
     pub attributes: Vec<Attribute>,
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
 pub struct Attribute {
     pub key: String,
     pub value: String,
 }
 
+// the sale price royalties are asked for, 1 sei in usei keeps the 2 decimals of the percentage exact
+static ROYALTY_PROBE_PRICE: u128 = 1_000_000;
+
+pub struct Royalty {
+    pub percentage: Option<Decimal>,
+    pub address: Option<String>,
+}
+
 pub async fn create_collection_if_not_exist(
     db: &DatabaseConnection,
-    client: &CosmosClient,
+    client: &StreamClient,
     address: String,
-    royalty: Option<f32>,
+    token_id: &str,
+    extension_royalty: Option<f32>,
 ) -> Result<(), AppError> {
     let collection = CollectionRespository::find_by_address(db, &address).await?;
 
@@ -49,9 +62,26 @@ pub async fn create_collection_if_not_exist(
         return Ok(());
     }
 
-    let metadata = get_collection_metadata(&address).await?;
-    let supply = client.get_cw721_contract_supply(&address).await?;
-    let info = client.get_cw721_contract_info(&address).await?;
+    let metadata = client
+        .collection_metadata
+        .get_collection_metadata(&address)
+        .await?;
+    let supply = client.chain.get_cw721_contract_supply(&address).await?;
+    let info = client.chain.get_cw721_contract_info(&address).await?;
+    let evm_address = client
+        .chain
+        .get_pointer(PointerType::Cw721, &address)
+        .await?;
+
+    // contracts without cw2981 only have the royalty_percentage of the token extension
+    let royalty = find_cw2981_royalty(client, &address, token_id)
+        .await
+        .unwrap_or(Royalty {
+            percentage: extension_royalty
+                .and_then(|percentage| Decimal::from_str(&percentage.to_string()).ok())
+                .map(|percentage| percentage.round_dp(2)),
+            address: None,
+        });
 
     CollectionRespository::create(
         db,
@@ -61,9 +91,9 @@ pub async fn create_collection_if_not_exist(
             name: info.name,
             metadata,
             supply: supply.count as i32,
-            royalty: royalty
-                .map(Decimal::from_f32_retain)
-                .map(Option::unwrap_or_default),
+            royalty: royalty.percentage,
+            royalty_address: royalty.address,
+            evm_address,
         },
     )
     .await?;
@@ -71,10 +101,32 @@ pub async fn create_collection_if_not_exist(
     Ok(())
 }
 
+// none when the contract does not implement cw2981
+pub async fn find_cw2981_royalty(
+    client: &StreamClient,
+    address: &str,
+    token_id: &str,
+) -> Option<Royalty> {
+    let info = client
+        .chain
+        .get_royalty_info(address, token_id, ROYALTY_PROBE_PRICE)
+        .await
+        .ok()?;
+
+    let amount = Decimal::from_str(&info.royalty_amount).unwrap_or_default();
+
+    Some(Royalty {
+        percentage: Some(
+            (amount * Decimal::ONE_HUNDRED / Decimal::from(ROYALTY_PROBE_PRICE)).round_dp(2),
+        ),
+        address: Some(info.address).filter(|address| !address.is_empty()),
+    })
+}
+
 // only update owner from cw721 stream
 pub async fn create_nft_or_update_owner_or_just_find(
     db: &DatabaseConnection,
-    client: &CosmosClient,
+    client: &StreamClient,
     token_address: String,
     token_id: String,
     owner: Option<String>,
@@ -87,14 +139,18 @@ pub async fn create_nft_or_update_owner_or_just_find(
         return Ok(nft.id);
     }
 
-    let info = client.get_nft_info(&token_address, &token_id).await?;
+    let info = client.chain.get_nft_info(&token_address, &token_id).await?;
 
-    let metadata = get_nft_metadata(&info.token_uri).await?;
+    let metadata = client
+        .nft_metadata
+        .get_nft_metadata(&info.token_uri)
+        .await?;
 
     create_collection_if_not_exist(
         db,
         client,
         token_address.to_owned(),
+        &token_id,
         info.extension
             .map(|ex| ex.royalty_percentage.unwrap_or_default()),
     )
@@ -146,6 +202,9 @@ pub async fn create_activity_transaction_and_point_on_sale(
     )
     .await?;
 
+    CollectionStatsRepository::record_sale(db, &params.collection_address, price, params.date)
+        .await?;
+
     TransactionRepository::create(
         &db,
         CreateTransactionParams {
@@ -156,6 +215,8 @@ pub async fn create_activity_transaction_and_point_on_sale(
             marketplace: params.marketplace,
             tx_hash: params.tx_hash,
             volume: price,
+            royalty: params.royalty,
+            royalty_address: params.royalty_address,
         },
     )
     .await?;
@@ -185,6 +246,48 @@ pub async fn create_activity_transaction_and_point_on_sale(
     Ok(db)
 }
 
+// lists are sorted by floor and volume, so they are dropped together with the collection itself
+pub async fn invalidate_collection_cache(
+    db: &DatabaseConnection,
+    client: &StreamClient,
+    collection_address: &str,
+) -> Result<(), AppError> {
+    let Some(pool) = &client.cache else {
+        return Ok(());
+    };
+
+    let mut tags = vec![
+        COLLECTIONS_TAG.to_owned(),
+        collection_tag(collection_address),
+    ];
+
+    // the detail page is reachable by slug as well
+    if let Some(slug) = CollectionRespository::find_by_address(db, collection_address)
+        .await?
+        .and_then(|collection| collection.slug)
+    {
+        tags.push(collection_tag(&slug));
+    }
+
+    cache::invalidate(pool, &tags).await
+}
+
+// recovery replays the block a stream stopped in, sales, points and activities are plain
+// inserts so the events handled before the stop must not run twice
+pub async fn is_already_handled(
+    db: &DatabaseConnection,
+    tx_hash: &str,
+    context: StreamContext,
+    event: &Event,
+) -> bool {
+    TracingRepository::is_handled(db, tx_hash, context, serde_json::json!(event))
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("error when find handled tx {} \n>>{}", tx_hash, e);
+            false
+        })
+}
+
 pub fn find_attribute(event: &Event, key: &str) -> Result<String, AppError> {
     event
         .attributes
@@ -246,6 +349,33 @@ impl Transaction {
     }
 }
 
+// txs fetched through tx_search during gap recovery, attributes are base64 encoded like the websocket ones
+impl From<tx::Response> for Transaction {
+    fn from(tx: tx::Response) -> Self {
+        let events = tx
+            .tx_result
+            .events
+            .into_iter()
+            .map(|event| Event {
+                r#type: event.kind,
+                attributes: event
+                    .attributes
+                    .iter()
+                    .map(|attribute| Attribute {
+                        key: to_utf8(attribute.key_str().unwrap_or_default()),
+                        value: to_utf8(attribute.value_str().unwrap_or_default()),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Transaction {
+            tx_hash: tx.hash.to_string(),
+            events,
+        }
+    }
+}
+
 pub struct CreateActivityTransactionAndPointOnSaleParams {
     pub buyer: String,
     pub date: DateTimeUtc,
@@ -257,4 +387,6 @@ pub struct CreateActivityTransactionAndPointOnSaleParams {
     pub collection_address: String,
     pub metadata: serde_json::Value,
     pub marketplace: Marketplace,
+    pub royalty: Decimal,
+    pub royalty_address: Option<String>,
 }
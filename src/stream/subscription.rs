@@ -0,0 +1,492 @@
+use super::shared::Transaction;
+use crate::{
+    database::repository::config as ConfigRepository, error::AppError, service::StreamClient,
+};
+use futures_util::{stream::SplitSink, SinkExt, StreamExt};
+use sea_orm::DatabaseConnection;
+use serde_json::Value;
+use std::{future::Future, pin::Pin, time::Duration};
+use tendermint_rpc::query::Query;
+use tokio::{net::TcpStream, time::Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+static KEEPALIVE_INTERVAL: Duration = Duration::from_secs(20);
+static DEFAULT_SILENCE_TIMEOUT: u64 = 60;
+static MIN_BACKOFF: Duration = Duration::from_secs(1);
+static MAX_BACKOFF: Duration = Duration::from_secs(60);
+// a connection that stayed up this long was healthy, so the next reconnect starts from the min backoff again
+static STABLE_AFTER: Duration = Duration::from_secs(60);
+static RECOVERY_PAGE_SIZE: u8 = 100;
+
+pub(super) type TxHandler<'r> = dyn Fn(
+        &'r DatabaseConnection,
+        &'r StreamClient,
+        Transaction,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'r>>
+    + Sync;
+
+type WsWriter = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+
+struct Subscription<'r> {
+    name: &'static str,
+    query: Query,
+    raw_query: String,
+    handler: &'r TxHandler<'r>,
+}
+
+// every query shares one websocket, the json-rpc id of a subscription is its index
+#[derive(Default)]
+pub struct Subscriptions<'r> {
+    subscriptions: Vec<Subscription<'r>>,
+}
+
+impl<'r> Subscriptions<'r> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the name keys the last processed height, so it must stay stable across deployments
+    pub fn add_subscription(
+        mut self,
+        name: &'static str,
+        query: Query,
+        handler: &'r TxHandler<'r>,
+    ) -> Self {
+        self.subscriptions.push(Subscription {
+            name,
+            raw_query: query.to_string(),
+            query,
+            handler,
+        });
+        self
+    }
+
+    pub async fn start(&self, db: &'r DatabaseConnection, client: &'r StreamClient) {
+        let mut backoff = MIN_BACKOFF;
+
+        loop {
+            let connected_at = Instant::now();
+
+            if let Err(error) = self.listen(db, client).await {
+                eprintln!("{}", error)
+            }
+
+            if connected_at.elapsed() >= STABLE_AFTER {
+                backoff = MIN_BACKOFF;
+            }
+
+            eprintln!("stream disconnected, reconnecting in {:?}", backoff);
+
+            tokio::time::sleep(backoff).await;
+
+            backoff = MAX_BACKOFF.min(backoff * 2);
+        }
+    }
+
+    async fn listen(
+        &self,
+        db: &'r DatabaseConnection,
+        client: &'r StreamClient,
+    ) -> Result<(), AppError> {
+        let wss_url = std::env::var("WSS_URL").expect("wss_url must be set");
+
+        let (ws_stream, _) = connect_async(wss_url).await?;
+
+        let (mut write, mut read) = ws_stream.split();
+
+        // the node drops subscriptions with the connection they were made on,
+        // so a reconnect always subscribes every query again from scratch
+        for (id, subscription) in self.subscriptions.iter().enumerate() {
+            write
+                .send(create_rpc_message("subscribe", id, &subscription.raw_query))
+                .await?;
+        }
+
+        // subscribed before recovering, so whatever lands meanwhile waits on the socket instead of being lost
+        let mut recovered_heights = Vec::new();
+
+        for subscription in &self.subscriptions {
+            recovered_heights.push(recover(db, client, subscription).await?);
+        }
+
+        let result = self
+            .route_messages(db, client, &mut write, &mut read, &recovered_heights)
+            .await;
+
+        // best effort, the connection may already be gone
+        for (id, subscription) in self.subscriptions.iter().enumerate() {
+            if write
+                .send(create_rpc_message(
+                    "unsubscribe",
+                    id,
+                    &subscription.raw_query,
+                ))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+
+        write.close().await.ok();
+
+        result
+    }
+
+    async fn route_messages<S>(
+        &self,
+        db: &'r DatabaseConnection,
+        client: &'r StreamClient,
+        write: &mut WsWriter,
+        read: &mut S,
+        recovered_heights: &[u64],
+    ) -> Result<(), AppError>
+    where
+        S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        let silence_timeout = Duration::from_secs(
+            std::env::var("STREAM_SILENCE_TIMEOUT")
+                .ok()
+                .and_then(|timeout| timeout.parse::<u64>().ok())
+                .unwrap_or(DEFAULT_SILENCE_TIMEOUT),
+        );
+
+        let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+        let mut last_seen = Instant::now();
+
+        loop {
+            let message = tokio::select! {
+                biased;
+
+                message = read.next() => message,
+
+                _ = keepalive.tick() => {
+                    write.send(Message::Ping(Vec::new())).await?;
+                    continue;
+                }
+
+                // our pings get answered even when no event matches, so silence means a half-open connection
+                _ = tokio::time::sleep_until(last_seen + silence_timeout) => {
+                    return Err(AppError::Unexpected(format!(
+                        "stream is silent for {:?}, connection is considered dead",
+                        silence_timeout
+                    )));
+                }
+            };
+
+            let Some(message) = message else {
+                return Ok(());
+            };
+
+            last_seen = Instant::now();
+
+            let message = match message? {
+                Message::Text(message) => message,
+                Message::Ping(payload) => {
+                    write.send(Message::Pong(payload)).await?;
+                    continue;
+                }
+                Message::Close(_) => return Ok(()),
+                _ => continue,
+            };
+
+            let message = serde_json::from_str::<Value>(&message)?;
+
+            let Some((id, subscription)) = self.find_subscription(&message) else {
+                println!("unexpected stream message {}", message);
+                continue;
+            };
+
+            if let Some(error) = message.get("error") {
+                eprintln!(
+                    "subscription {} is rejected \n>>{}",
+                    subscription.raw_query, error
+                );
+                continue;
+            }
+
+            // the subscribe acknowledgement has an empty result, so this time is perfect to tell that stream is working
+            if message.get("result").is_some_and(is_empty_object) {
+                println!("🦀 listening stream {}", subscription.name);
+                continue;
+            }
+
+            let height = find_height(&message);
+
+            // already handled by the gap recovery of this connection
+            if height.is_some_and(|height| height <= recovered_heights[id]) {
+                continue;
+            }
+
+            if let Some(height) = height {
+                save_completed_height(db, subscription.name, height).await?;
+            }
+
+            // a malformed tx should not tear down the other subscriptions on this socket
+            match Transaction::try_from_value(message) {
+                Ok(tx) => (subscription.handler)(db, client, tx).await,
+                Err(error) => {
+                    eprintln!("error when parse tx of {} \n>>{}", subscription.name, error);
+                    continue;
+                }
+            }
+
+            // handling is not silence, only count from the moment we are back to reading
+            last_seen = Instant::now();
+        }
+    }
+
+    // events echo their query back, acknowledgements and errors only carry the request id
+    fn find_subscription(&self, message: &Value) -> Option<(usize, &Subscription<'r>)> {
+        let by_query = message
+            .get("result")
+            .and_then(|result| result.get("query"))
+            .and_then(Value::as_str)
+            .and_then(|query| {
+                self.subscriptions
+                    .iter()
+                    .enumerate()
+                    .find(|(_, subscription)| subscription.raw_query == query)
+            });
+
+        by_query.or_else(|| {
+            let id = match message.get("id")? {
+                Value::String(id) => id.parse::<usize>().ok()?,
+                Value::Number(id) => id.as_u64()? as usize,
+                _ => return None,
+            };
+
+            self.subscriptions
+                .get(id)
+                .map(|subscription| (id, subscription))
+        })
+    }
+}
+
+// replays what happened since the last completed height, the first run has nothing to recover
+async fn recover<'r>(
+    db: &'r DatabaseConnection,
+    client: &'r StreamClient,
+    subscription: &Subscription<'r>,
+) -> Result<u64, AppError> {
+    let Some(last_height) = find_saved_height(db, subscription.name).await? else {
+        return Ok(0);
+    };
+
+    let query = subscription.query.clone().and_gt("tx.height", last_height);
+
+    let mut page = 1;
+    let mut recovered = 0;
+    let mut recovered_height = last_height;
+
+    loop {
+        let res = client
+            .chain
+            .search_tx(query.clone(), page, RECOVERY_PAGE_SIZE)
+            .await?;
+
+        if res.txs.is_empty() {
+            break;
+        }
+
+        recovered += res.txs.len();
+
+        for tx in res.txs {
+            let height = tx.height.value();
+
+            save_completed_height(db, subscription.name, height).await?;
+
+            (subscription.handler)(db, client, Transaction::from(tx)).await;
+
+            recovered_height = height;
+        }
+
+        if recovered >= res.total_count as usize {
+            break;
+        }
+
+        page += 1;
+    }
+
+    // the search only returns committed blocks, so the last one is complete as well
+    if recovered_height > last_height {
+        save_height(db, subscription.name, recovered_height).await?;
+    }
+
+    if recovered > 0 {
+        println!(
+            "🦀 recovered {} txs of {} from height {}",
+            recovered, subscription.name, last_height
+        );
+    }
+
+    Ok(recovered_height)
+}
+
+async fn find_saved_height(db: &DatabaseConnection, name: &str) -> Result<Option<u64>, AppError> {
+    let height = ConfigRepository::find_by_key(db, &height_key(name)).await?;
+
+    height
+        .map(|height| height.parse::<u64>())
+        .transpose()
+        .map_err(|e| AppError::Unexpected(e.to_string()))
+}
+
+async fn save_height(db: &DatabaseConnection, name: &str, height: u64) -> Result<(), AppError> {
+    ConfigRepository::upsert(db, &height_key(name), height.to_string()).await?;
+
+    Ok(())
+}
+
+// txs arrive in block order, one of height h means every block before it is fully handled.
+// h itself is not, recovery replays its whole block and the handlers skip the events they
+// already recorded in stream_tx
+async fn save_completed_height(
+    db: &DatabaseConnection,
+    name: &str,
+    height: u64,
+) -> Result<(), AppError> {
+    save_height(db, name, height.saturating_sub(1)).await
+}
+
+fn height_key(name: &str) -> String {
+    format!("{}_stream_height", name)
+}
+
+fn find_height(message: &Value) -> Option<u64> {
+    message
+        .get("result")
+        .and_then(|v| v.get("events"))
+        .and_then(|v| v.get("tx.height"))
+        .and_then(|v| v.get(0))
+        .and_then(Value::as_str)
+        .and_then(|height| height.parse::<u64>().ok())
+}
+
+fn is_empty_object(value: &Value) -> bool {
+    value.as_object().is_some_and(|object| object.is_empty())
+}
+
+fn create_rpc_message(method: &str, id: usize, query: &str) -> Message {
+    let msg = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "id": id.to_string(),
+        "params": {
+          "query": query
+        }
+    });
+
+    Message::text(msg.to_string())
+}
+
+#[cfg(all(test, feature = "fake"))]
+mod tests {
+    use super::*;
+    use crate::{
+        database,
+        service::fake::FakeChainClient,
+        stream::pallet::{
+            self,
+            tests::{
+                chain_event, count, create_auction, stream_client, BUYER, BUY_NOW_TX, COLLECTION,
+                TOKEN_ID,
+            },
+        },
+    };
+    use std::str::FromStr;
+    use tendermint::{abci, Hash};
+    use tendermint_rpc::{endpoint::tx, query::EventType};
+
+    static RELIST_TX: &str = "3333333333333333333333333333333333333333333333333333333333333333";
+    static NEXT_BLOCK_TX: &str = "4444444444444444444444444444444444444444444444444444444444444444";
+
+    fn chain_tx(tx_hash: &str, height: u32, events: Vec<abci::Event>) -> tx::Response {
+        tx::Response {
+            hash: Hash::from_str(tx_hash).unwrap(),
+            height: height.into(),
+            index: 0,
+            tx_result: abci::types::ExecTxResult {
+                events,
+                ..Default::default()
+            },
+            tx: vec![],
+            proof: None,
+        }
+    }
+
+    fn pallet_tx(tx_hash: &str, height: u32, action: &str) -> tx::Response {
+        chain_tx(
+            tx_hash,
+            height,
+            vec![
+                chain_event(
+                    action,
+                    &[("collection_address", COLLECTION), ("token_id", TOKEN_ID)],
+                ),
+                chain_event("wasm", &[("recipent", BUYER)]),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn recover_replays_the_block_a_crash_stopped_in() {
+        let Some(db) = database::test::connect().await else {
+            return;
+        };
+
+        let chain = FakeChainClient::default();
+        let client = stream_client(chain.clone());
+
+        let query = Query::from(EventType::Tx);
+        let subscription = Subscription {
+            name: "test",
+            raw_query: query.to_string(),
+            query,
+            handler: &|db, client, tx| Box::pin(pallet::tx_handler(db, client, tx)),
+        };
+
+        create_auction(&db, &client, &chain, TOKEN_ID, "5000000").await;
+
+        // block 11 sold the nft and listed it again, both were handled when the stream went down
+        let block = [
+            pallet_tx(BUY_NOW_TX, 11, "wasm-buy_now"),
+            pallet_tx(RELIST_TX, 11, "wasm-create_auction"),
+        ];
+
+        for tx in block {
+            chain.set_tx(&tx.hash.to_string(), tx.clone());
+            chain.push_searchable_tx(tx.clone());
+
+            save_completed_height(&db, subscription.name, 11)
+                .await
+                .unwrap();
+            (subscription.handler)(&db, &client, Transaction::from(tx)).await;
+        }
+
+        assert_eq!(
+            find_saved_height(&db, subscription.name).await.unwrap(),
+            Some(10)
+        );
+
+        chain.push_searchable_tx(chain_tx(NEXT_BLOCK_TX, 12, vec![]));
+
+        let recovered_height = recover(&db, &client, &subscription).await.unwrap();
+
+        assert_eq!(recovered_height, 12);
+        assert_eq!(
+            find_saved_height(&db, subscription.name).await.unwrap(),
+            Some(12)
+        );
+        // the replayed buy found the new listing, it must not have been sold a second time
+        assert_eq!(count(&db, r#"SELECT count(*) FROM "transaction""#).await, 1);
+        assert_eq!(
+            count(&db, r#"SELECT "sales"::bigint FROM "collection_stats""#).await,
+            1
+        );
+        assert_eq!(
+            count(&db, r#"SELECT "listed"::bigint FROM "collection_stats""#).await,
+            1
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{
+    database,
+    schedule::run_background,
+    server::{create_redis_pool, run_server},
+    stream::{
+        run_contract_stream, run_cw721_stream, run_erc721_stream, run_launchpad_stream,
+        run_pallet_stream,
+    },
+    watcher::run_watcher,
+};
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use chrono::{DateTime, Utc};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc, time::Duration};
+use tokio::{sync::RwLock, task::LocalSet, time::Instant};
+
+static MIN_BACKOFF: Duration = Duration::from_secs(1);
+static MAX_BACKOFF: Duration = Duration::from_secs(60);
+// a component that stayed up this long is healthy again, so the next crash restarts quickly
+static STABLE_AFTER: Duration = Duration::from_secs(300);
+
+static DEFAULT_COMPONENTS: [Component; 3] = [
+    Component::Server,
+    Component::ContractStream,
+    Component::Erc721Stream,
+];
+
+// contract-stream subscribes to the events of these on its own websocket
+static CONTRACT_STREAM_PARTS: [Component; 3] = [
+    Component::PalletStream,
+    Component::Cw721Stream,
+    Component::LaunchpadStream,
+];
+
+type Statuses = Arc<RwLock<HashMap<&'static str, ComponentStatus>>>;
+
+// the watcher future is not Send, it is the only component kept on the local set
+enum Task {
+    Threaded(Pin<Box<dyn Future<Output = ()> + Send>>),
+    Local(Pin<Box<dyn Future<Output = ()>>>),
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+enum Component {
+    Server,
+    PalletStream,
+    Cw721Stream,
+    Erc721Stream,
+    LaunchpadStream,
+    ContractStream,
+    Schedule,
+    Watcher,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum ComponentStatus {
+    Running {
+        since: DateTime<Utc>,
+        restarts: u32,
+    },
+    Restarting {
+        restarts: u32,
+        reason: String,
+        retry_at: DateTime<Utc>,
+    },
+}
+
+#[derive(Clone)]
+struct Resources {
+    db: DatabaseConnection,
+    redis_pool: deadpool_redis::Pool,
+}
+
+// components are selected by their binary name, from the cli args first then SUPERVISOR_COMPONENTS,
+// e.g. `supervisor server cw721-stream` or SUPERVISOR_COMPONENTS=server,cw721-stream
+pub async fn supervisor() {
+    dotenv::dotenv().ok();
+
+    let components = selected_components();
+
+    let resources = Resources {
+        db: database::connect().await,
+        redis_pool: create_redis_pool(),
+    };
+
+    let statuses: Statuses = Arc::new(RwLock::new(HashMap::new()));
+
+    // supervisors only wait on their component, the components themselves run on the worker
+    // threads so a busy stream handler can not stall the api or the other streams
+    let local = LocalSet::new();
+
+    for component in components {
+        local.spawn_local(supervise(component, resources.clone(), statuses.clone()));
+    }
+
+    local.spawn_local(serve_health(statuses));
+
+    local.await;
+}
+
+async fn supervise(component: Component, resources: Resources, statuses: Statuses) {
+    let mut restarts = 0;
+    let mut backoff = MIN_BACKOFF;
+
+    loop {
+        statuses.write().await.insert(
+            component.name(),
+            ComponentStatus::Running {
+                since: Utc::now(),
+                restarts,
+            },
+        );
+
+        println!("🦀 supervisor started {}", component.name());
+
+        let started_at = Instant::now();
+
+        let handle = match component.run(resources.clone()) {
+            Task::Threaded(task) => tokio::spawn(task),
+            Task::Local(task) => tokio::task::spawn_local(task),
+        };
+
+        // panics are caught by the join handle, so a crash never takes the other components down
+        let reason = match handle.await {
+            Ok(_) => "exited".to_owned(),
+            Err(error) => error.to_string(),
+        };
+
+        if started_at.elapsed() >= STABLE_AFTER {
+            backoff = MIN_BACKOFF;
+        }
+
+        restarts += 1;
+
+        eprintln!(
+            "supervisor component {} stopped ({}), restarting in {:?}",
+            component.name(),
+            reason,
+            backoff
+        );
+
+        statuses.write().await.insert(
+            component.name(),
+            ComponentStatus::Restarting {
+                restarts,
+                reason,
+                retry_at: Utc::now() + backoff,
+            },
+        );
+
+        tokio::time::sleep(backoff).await;
+
+        backoff = MAX_BACKOFF.min(backoff * 2);
+    }
+}
+
+async fn serve_health(statuses: Statuses) {
+    let address = std::env::var("SUPERVISOR_ADDRESS").unwrap_or("0.0.0.0:8099".to_owned());
+
+    let app = Router::new()
+        .route("/health", get(get_health))
+        .with_state(statuses);
+
+    let listener = tokio::net::TcpListener::bind(&address).await.unwrap();
+
+    println!("🦀 supervisor health is reported on {}", address);
+
+    axum::serve(listener, app).await.unwrap();
+}
+
+async fn get_health(
+    State(statuses): State<Statuses>,
+) -> (StatusCode, Json<HashMap<&'static str, ComponentStatus>>) {
+    let statuses = statuses.read().await.clone();
+
+    let is_healthy = statuses
+        .values()
+        .all(|status| matches!(status, ComponentStatus::Running { .. }));
+
+    let code = if is_healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (code, Json(statuses))
+}
+
+fn selected_components() -> Vec<Component> {
+    let mut names: Vec<String> = std::env::args().skip(1).collect();
+
+    if names.is_empty() {
+        names = std::env::var("SUPERVISOR_COMPONENTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(ToOwned::to_owned)
+            .collect();
+    }
+
+    if names.is_empty() {
+        return DEFAULT_COMPONENTS.to_vec();
+    }
+
+    let mut components = Vec::new();
+
+    for name in names {
+        let component = Component::from_name(&name)
+            .unwrap_or_else(|| panic!("unknown supervisor component {}", name));
+
+        if !components.contains(&component) {
+            components.push(component);
+        }
+    }
+
+    if components.contains(&Component::ContractStream) {
+        if let Some(part) = CONTRACT_STREAM_PARTS
+            .iter()
+            .find(|part| components.contains(part))
+        {
+            panic!(
+                "contract-stream already handles the events of {}, select one of them",
+                part.name()
+            );
+        }
+    }
+
+    components
+}
+
+impl Component {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Server => "server",
+            Self::PalletStream => "pallet-stream",
+            Self::Cw721Stream => "cw721-stream",
+            Self::Erc721Stream => "erc721-stream",
+            Self::LaunchpadStream => "launchpad-stream",
+            Self::ContractStream => "contract-stream",
+            Self::Schedule => "schedule",
+            Self::Watcher => "watcher",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "server" => Some(Self::Server),
+            "pallet-stream" => Some(Self::PalletStream),
+            "cw721-stream" => Some(Self::Cw721Stream),
+            "erc721-stream" => Some(Self::Erc721Stream),
+            "launchpad-stream" => Some(Self::LaunchpadStream),
+            "contract-stream" => Some(Self::ContractStream),
+            "schedule" => Some(Self::Schedule),
+            "watcher" => Some(Self::Watcher),
+            _ => None,
+        }
+    }
+
+    fn run(&self, resources: Resources) -> Task {
+        let Resources { db, redis_pool } = resources;
+
+        match self {
+            Self::Server => Task::Threaded(Box::pin(run_server(db, redis_pool))),
+            Self::PalletStream => Task::Threaded(Box::pin(run_pallet_stream(db))),
+            Self::Cw721Stream => Task::Threaded(Box::pin(run_cw721_stream(db))),
+            Self::Erc721Stream => Task::Threaded(Box::pin(run_erc721_stream(db))),
+            Self::LaunchpadStream => Task::Threaded(Box::pin(run_launchpad_stream(db))),
+            Self::ContractStream => Task::Threaded(Box::pin(run_contract_stream(db))),
+            Self::Schedule => Task::Threaded(Box::pin(run_background(db))),
+            Self::Watcher => Task::Local(Box::pin(run_watcher(db))),
+        }
+    }
+}
@@ -1,19 +1,33 @@
 mod listener;
 mod trigger;
 
-use sea_orm::Database;
+use crate::{
+    database::{self, repository::nft_activity as NftActivityRepository},
+    error::AppError,
+    r#static::ACTIVITY_EVENTS_CHANNEL,
+    server::create_redis_pool,
+};
+use deadpool_redis::redis::AsyncCommands;
+use sea_orm::DatabaseConnection;
 use serde_json::Value;
-use trigger::{create_stream_tx_trigger, Channel};
+use trigger::{create_nft_activity_trigger, create_stream_tx_trigger, Channel};
 
 pub async fn watcher() {
     dotenv::dotenv().ok();
-    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
-    let db = Database::connect(&db_url).await.unwrap();
+    run_watcher(database::connect().await).await
+}
 
+pub async fn run_watcher(db: DatabaseConnection) {
     create_stream_tx_trigger(&db)
         .await
         .unwrap_or_else(|e| eprintln!("fail when create stream tx trigger >>{}", e));
 
+    create_nft_activity_trigger(&db)
+        .await
+        .unwrap_or_else(|e| eprintln!("fail when create nft activity trigger >>{}", e));
+
+    let redis_pool = create_redis_pool();
+
     listener::Listener::new()
         .add_watcher(Channel::StreamTx, &|_db, payload: Value| {
             Box::pin(async move {
@@ -21,7 +35,45 @@ pub async fn watcher() {
                 Ok(())
             })
         })
+        .add_watcher(Channel::NftActivity, &move |db, payload: Value| {
+            let db = db.clone();
+            let redis_pool = redis_pool.clone();
+
+            // a failed publish only costs the live push, clients catch up when they resume
+            Box::pin(async move {
+                publish_activity(&db, &redis_pool, payload)
+                    .await
+                    .unwrap_or_else(|e| eprintln!("fail when publish activity >>{}", e));
+                Ok(())
+            })
+        })
         .start(&db)
         .await
         .unwrap_or_else(|e| eprintln!("{}", e));
 }
+
+async fn publish_activity(
+    db: &DatabaseConnection,
+    redis_pool: &deadpool_redis::Pool,
+    payload: Value,
+) -> Result<(), AppError> {
+    let id = payload
+        .get("id")
+        .and_then(Value::as_i64)
+        .ok_or(AppError::Unexpected(format!(
+            "unexpected nft activity payload {}",
+            payload
+        )))?;
+
+    let Some(activity) = NftActivityRepository::find_activity_by_id(db, id as i32).await? else {
+        return Ok(());
+    };
+
+    let mut redis = redis_pool.get().await?;
+
+    redis
+        .publish::<_, _, ()>(ACTIVITY_EVENTS_CHANNEL, serde_json::to_string(&activity)?)
+        .await?;
+
+    Ok(())
+}
@@ -5,6 +5,7 @@ use crate::error::AppError;
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Channel {
     StreamTx,
+    NftActivity,
 }
 
 pub async fn create_stream_tx_trigger(db: &DatabaseConnection) -> Result<(), AppError> {
@@ -54,18 +55,65 @@ pub async fn create_stream_tx_trigger(db: &DatabaseConnection) -> Result<(), App
     Ok(())
 }
 
+// notify payloads are capped at 8000 bytes, so only the id is sent and listeners read the row themselves
+pub async fn create_nft_activity_trigger(db: &DatabaseConnection) -> Result<(), AppError> {
+    let channel = Channel::NFT_ACTIVITY_CHANNEL;
+    let fn_adapter = "nft_activity_fn()";
+
+    let create_nft_activity_function_stm = format!(
+        r#"
+            CREATE OR REPLACE FUNCTION {}
+            RETURNS TRIGGER AS $$
+            BEGIN
+                PERFORM pg_notify('{}', json_build_object('id', NEW.id)::text);
+
+                RETURN NEW;
+            END;
+            $$ LANGUAGE plpgsql;
+    "#,
+        fn_adapter, channel
+    );
+
+    let create_nft_activity_trigger_stm = format!(
+        r#"
+            CREATE OR REPLACE TRIGGER nft_activity_trigger
+            AFTER INSERT
+            ON nft_activity
+            FOR EACH ROW EXECUTE PROCEDURE {};
+    "#,
+        fn_adapter
+    );
+
+    db.execute(Statement::from_string(
+        DbBackend::Postgres,
+        create_nft_activity_function_stm,
+    ))
+    .await?;
+
+    db.execute(Statement::from_string(
+        DbBackend::Postgres,
+        create_nft_activity_trigger_stm,
+    ))
+    .await?;
+
+    Ok(())
+}
+
 impl Channel {
     const STREAM_TX_CHANNEL: &'static str = "stream_tx_channel";
+    const NFT_ACTIVITY_CHANNEL: &'static str = "nft_activity_channel";
 
     pub fn to_str(self) -> &'static str {
         match self {
             Self::StreamTx => Self::STREAM_TX_CHANNEL,
+            Self::NftActivity => Self::NFT_ACTIVITY_CHANNEL,
         }
     }
 
     pub fn from_str(channel: &str) -> Self {
         match channel {
             Self::STREAM_TX_CHANNEL => Self::StreamTx,
+            Self::NFT_ACTIVITY_CHANNEL => Self::NftActivity,
             _ => panic!("unknown channel"),
         }
     }